@@ -0,0 +1,273 @@
+//! Benchmarks for the dispatchables of the pallet
+
+use super::*;
+use crate::Pallet as Erc1155;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::{pallet_prelude::MaxEncodedLen, traits::{EnsureOrigin, Get}};
+use frame_system::RawOrigin;
+use sp_std::vec;
+
+const SEED: u32 = 0;
+/// Supply and minimum balance of the tokens moved by the benchmarks
+const SUPPLY: u32 = 1_000;
+const MIN_BALANCE: u32 = 10;
+/// Balance left behind by the worst case transfers and burns, below `MIN_BALANCE`
+const DUST: u32 = 5;
+
+/// Create a token owned by `owner`, who also holds every role
+fn create_default_token<T: Config>(owner: &T::AccountId, supply: u32) -> T::TokenId {
+    Erc1155::<T>::do_create_token(owner.clone(), owner.clone(), supply.into(), None, false, Zero::zero())
+        .expect("token is created")
+}
+
+/// Give `who` `MaxLocks` locks on `id`. They lock nothing, so `who` can still be reaped: `set_lock`
+/// never writes such locks, but they bound both decoding the locks and reaping the account
+fn add_locks<T: Config>(who: &T::AccountId, id: &T::TokenId) {
+    let locks = (0..T::MaxLocks::get())
+        .map(|i| BalanceLock { id: (i as u64).to_le_bytes(), amount: Zero::zero(), reasons: WithdrawReasons::all() })
+        .collect();
+    Erc1155::<T>::update_locks(who, id, locks);
+}
+
+/// Create a token with a minimum balance owned by `owner`, who holds `MaxLocks` locks on it.
+/// Moving all but `DUST` of its balance reaps `owner`
+fn create_dusty_token<T: Config>(owner: &T::AccountId, supply: u32) -> T::TokenId {
+    let id = Erc1155::<T>::do_create_token(owner.clone(), owner.clone(), supply.into(), None, false, MIN_BALANCE.into())
+        .expect("token is created");
+    add_locks::<T>(owner, &id);
+    id
+}
+
+/// Supply of the tokens of a batch of `n` entries, enough for every entry to move at least 1
+fn batch_supply(n: u32) -> u32 {
+    SUPPLY.saturating_add(n)
+}
+
+/// Create `d` tokens from `create_dusty_token`, and a batch of `d + r` entries cycling over them so
+/// `r` entries repeat an id. The entries of every token add up to all but `DUST` of its supply
+fn create_batch<T: Config>(owner: &T::AccountId, d: u32, r: u32) -> Vec<(T::TokenId, T::Balance)> {
+    let n = d + r;
+    let supply = batch_supply(n);
+    let ids = (0..d).map(|_| create_dusty_token::<T>(owner, supply)).collect::<Vec<_>>();
+    (0..n)
+        .map(|i| {
+            let j = i % d;
+            let entries = (n - j + d - 1) / d;
+            // the first entry of a token moves what its other entries, 1 each, don't
+            let value = if i < d { supply - DUST - (entries - 1) } else { 1 };
+            (ids[j as usize], value.into())
+        })
+        .collect()
+}
+
+/// Total issuance of the first `d` tokens of a batch from `create_batch`
+fn batch_issuance<T: Config>(id_values: &[(T::TokenId, T::Balance)], d: u32) -> T::Balance {
+    id_values
+        .iter()
+        .take(d as usize)
+        .fold(Zero::zero(), |acc, &(id, _)| acc + Erc1155::<T>::issuance(id).unwrap_or_else(Zero::zero))
+}
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+    frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+benchmarks! {
+    safe_transfer {
+        let caller: T::AccountId = whitelisted_caller();
+        let target: T::AccountId = account("target", 0, SEED);
+        let id = create_dusty_token::<T>(&caller, SUPPLY);
+        // the recipient already holds the token, so both balances are read and written
+        Erc1155::<T>::do_transfer(&caller, &target, &id, MIN_BALANCE.into(), ExistenceRequirement::AllowDeath)?;
+        let target_lookup = T::Lookup::unlookup(target.clone());
+    }: _(RawOrigin::Signed(caller.clone()), target_lookup, id, (SUPPLY - MIN_BALANCE - DUST).into())
+    verify {
+        assert_eq!(Erc1155::<T>::balance_of(&target, id), Some((SUPPLY - DUST).into()));
+        assert_eq!(Erc1155::<T>::balance_of(&caller, id), None);
+    }
+
+    safe_batch_transfer {
+        // `d` distinct ids and `r` repeated entries, `d + r` never exceeds `MaxBatchSize`
+        let d in 1 .. (T::MaxBatchSize::get() / 2).max(1);
+        let r in 0 .. T::MaxBatchSize::get() / 2;
+        let caller: T::AccountId = whitelisted_caller();
+        let target: T::AccountId = account("target", 0, SEED);
+        let id_values = create_batch::<T>(&caller, d, r);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+    }: _(RawOrigin::Signed(caller.clone()), target_lookup, id_values.clone())
+    verify {
+        let (ids, values) = id_values.into_iter().unzip();
+        assert_last_event::<T>(Event::TransferBatch(caller.clone(), Some(caller), Some(target), ids, values).into());
+    }
+
+    set_approval_for_all {
+        let caller: T::AccountId = whitelisted_caller();
+        let operator: T::AccountId = account("operator", 0, SEED);
+        let operator_lookup = T::Lookup::unlookup(operator.clone());
+    }: _(RawOrigin::Signed(caller.clone()), operator_lookup, true)
+    verify {
+        assert!(Erc1155::<T>::operator_approvals(&caller, &operator));
+    }
+
+    transfer_from {
+        let caller: T::AccountId = whitelisted_caller();
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let target: T::AccountId = account("target", 0, SEED);
+        let id = create_dusty_token::<T>(&owner, SUPPLY);
+        Erc1155::<T>::do_transfer(&owner, &target, &id, MIN_BALANCE.into(), ExistenceRequirement::AllowDeath)?;
+        <Erc1155<T> as ERC1155<T::AccountId>>::set_approval_for_all(&owner, &caller, true)?;
+        let owner_lookup = T::Lookup::unlookup(owner.clone());
+        let target_lookup = T::Lookup::unlookup(target.clone());
+    }: _(RawOrigin::Signed(caller.clone()), owner_lookup, target_lookup, id, (SUPPLY - MIN_BALANCE - DUST).into())
+    verify {
+        assert_eq!(Erc1155::<T>::balance_of(&target, id), Some((SUPPLY - DUST).into()));
+        assert_eq!(Erc1155::<T>::balance_of(&owner, id), None);
+    }
+
+    burn {
+        let caller: T::AccountId = whitelisted_caller();
+        let id = create_dusty_token::<T>(&caller, SUPPLY);
+    }: _(RawOrigin::Signed(caller.clone()), id, (SUPPLY - DUST).into())
+    verify {
+        assert!(Erc1155::<T>::issuance(id).unwrap_or_else(Zero::zero).is_zero());
+    }
+
+    admin_burn {
+        let caller: T::AccountId = whitelisted_caller();
+        let target: T::AccountId = account("target", 0, SEED);
+        let id = create_dusty_token::<T>(&caller, SUPPLY);
+        Erc1155::<T>::do_transfer(&caller, &target, &id, (SUPPLY - MIN_BALANCE).into(), ExistenceRequirement::AllowDeath)?;
+        add_locks::<T>(&target, &id);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+    }: _(RawOrigin::Signed(caller.clone()), target_lookup, id, (SUPPLY - MIN_BALANCE - DUST).into())
+    verify {
+        assert_eq!(Erc1155::<T>::balance_of(&target, id), None);
+        assert_eq!(Erc1155::<T>::issuance(id), Some(MIN_BALANCE.into()));
+    }
+
+    burn_batch {
+        // `d` distinct ids and `r` repeated entries, `d + r` never exceeds `MaxBatchSize`
+        let d in 1 .. (T::MaxBatchSize::get() / 2).max(1);
+        let r in 0 .. T::MaxBatchSize::get() / 2;
+        let caller: T::AccountId = whitelisted_caller();
+        let id_values = create_batch::<T>(&caller, d, r);
+    }: _(RawOrigin::Signed(caller.clone()), id_values.clone())
+    verify {
+        assert!(batch_issuance::<T>(&id_values, d).is_zero());
+    }
+
+    create_token {
+        let origin = T::CreateOrigin::successful_origin();
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let owner_lookup = T::Lookup::unlookup(owner.clone());
+        let id = Erc1155::<T>::free_token_id()?;
+    }: _<T::Origin>(origin, owner_lookup, 1_000u32.into(), Some(10_000u32.into()), false, 1u32.into())
+    verify {
+        assert_eq!(Erc1155::<T>::balance_of(&owner, id), Some(1_000u32.into()));
+    }
+
+    create_unique_token {
+        let origin = T::CreateOrigin::successful_origin();
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let owner_lookup = T::Lookup::unlookup(owner.clone());
+        let id = Erc1155::<T>::free_token_id()?;
+    }: _<T::Origin>(origin, owner_lookup)
+    verify {
+        assert_eq!(Erc1155::<T>::owner_of(id), Some(owner));
+    }
+
+    mint {
+        let caller: T::AccountId = whitelisted_caller();
+        let target: T::AccountId = account("target", 0, SEED);
+        let id = create_default_token::<T>(&caller, 1_000);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+    }: _(RawOrigin::Signed(caller.clone()), target_lookup, id, 100u32.into())
+    verify {
+        assert_eq!(Erc1155::<T>::issuance(id), Some(1_100u32.into()));
+    }
+
+    mint_batch {
+        // `d` distinct ids and `r` repeated entries, `d + r` never exceeds `MaxBatchSize`
+        let d in 1 .. (T::MaxBatchSize::get() / 2).max(1);
+        let r in 0 .. T::MaxBatchSize::get() / 2;
+        let caller: T::AccountId = whitelisted_caller();
+        let target: T::AccountId = account("target", 0, SEED);
+        let id_values = create_batch::<T>(&caller, d, r);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+    }: _(RawOrigin::Signed(caller.clone()), target_lookup, id_values.clone())
+    verify {
+        let supply = T::Balance::from(batch_supply(d + r));
+        assert_eq!(batch_issuance::<T>(&id_values, d), T::Balance::from(d) * (supply + supply - DUST.into()));
+    }
+
+    set_uri {
+        let origin = T::ForceOrigin::successful_origin();
+        let caller: T::AccountId = whitelisted_caller();
+        let id = create_default_token::<T>(&caller, 1_000);
+        // longest URI `TokenInfo` accepts
+        let mut uri = vec![b'u'; T::TokenInfo::max_encoded_len()];
+        while T::TokenInfo::try_from(uri.clone()).is_err() {
+            uri.pop();
+        }
+    }: _<T::Origin>(origin, Some(id), uri.clone())
+    verify {
+        assert_last_event::<T>(Event::URI(uri, id).into());
+    }
+
+    transfer_ownership {
+        let caller: T::AccountId = whitelisted_caller();
+        let target: T::AccountId = account("target", 0, SEED);
+        let id = create_default_token::<T>(&caller, 1_000);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+    }: _(RawOrigin::Signed(caller), id, target_lookup)
+    verify {
+        assert_last_event::<T>(Event::OwnerChanged(id, target).into());
+    }
+
+    set_team {
+        let caller: T::AccountId = whitelisted_caller();
+        let issuer: T::AccountId = account("issuer", 0, SEED);
+        let admin: T::AccountId = account("admin", 0, SEED);
+        let freezer: T::AccountId = account("freezer", 0, SEED);
+        let id = create_default_token::<T>(&caller, 1_000);
+    }: _(
+        RawOrigin::Signed(caller),
+        id,
+        T::Lookup::unlookup(issuer.clone()),
+        T::Lookup::unlookup(admin.clone()),
+        T::Lookup::unlookup(freezer.clone())
+    )
+    verify {
+        assert_last_event::<T>(Event::TeamChanged(id, issuer, admin, freezer).into());
+    }
+
+    freeze {
+        let caller: T::AccountId = whitelisted_caller();
+        let id = create_default_token::<T>(&caller, 1_000);
+    }: _(RawOrigin::Signed(caller), id)
+    verify {
+        assert_last_event::<T>(Event::Frozen(id).into());
+    }
+
+    thaw {
+        let caller: T::AccountId = whitelisted_caller();
+        let id = create_default_token::<T>(&caller, 1_000);
+        Erc1155::<T>::set_frozen(&caller, &id, true)?;
+    }: _(RawOrigin::Signed(caller), id)
+    verify {
+        assert_last_event::<T>(Event::Thawed(id).into());
+    }
+
+    balance_of_batch {
+        let n in 1 .. T::MaxBatchSize::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let owner_ids = create_batch::<T>(&caller, n, 0)
+            .into_iter()
+            .map(|(id, _)| (caller.clone(), id))
+            .collect::<Vec<_>>();
+    }: {
+        <Erc1155<T> as ERC1155<T::AccountId>>::balance_of_batch(owner_ids.iter())?;
+    }
+}
+
+impl_benchmark_test_suite!(Erc1155, crate::mock::new_test_ext(), crate::mock::Test);
@@ -1,25 +1,27 @@
 use codec::FullCodec;
-use frame_support::{traits::Imbalance, dispatch::{DispatchError, DispatchResult}};
+use frame_support::{dispatch::{DispatchError, DispatchResult}, weights::Weight};
 use sp_runtime::traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize};
-use sp_std::{fmt::Debug, vec::Vec};
+use sp_std::{borrow::Borrow, fmt::Debug, vec::Vec};
 
 
 pub trait ERC1155<AccountId> {
     type TokenId: AtLeast32BitUnsigned + FullCodec + Default + Copy + MaybeSerializeDeserialize + Debug;
     type Balance: AtLeast32BitUnsigned + FullCodec + Copy + MaybeSerializeDeserialize + Debug + Default;
-    type PositiveImbalance: Imbalance<Self::Balance>;
-    type NegativeImbalance: Imbalance<Self::Balance>;
 
-    fn safe_transfer_from(from: &AccountId, to: &AccountId, id: &Self::TokenId, value: Self::Balance, calldata: Option<Vec<u8>>) -> DispatchResult;
+    /// Transfer `value` of `id` from `from` to `to`.
+    /// `operator` must be either `from` or an operator approved by `from`
+    fn safe_transfer_from(operator: &AccountId, from: &AccountId, to: &AccountId, id: &Self::TokenId, value: Self::Balance, calldata: Option<Vec<u8>>) -> DispatchResult;
 
+    /// Transfer every value of `id_values` from `from` to `to`.
+    /// Implementations may reject batches above a maximum length
     fn safe_batch_transfer_from(
-        from: &AccountId, to: &AccountId,
-        id_values: impl Iterator<Item = impl AsRef<(Self::TokenId, Self::Balance)>>,
+        operator: &AccountId, from: &AccountId, to: &AccountId,
+        id_values: impl Iterator<Item = impl Borrow<(Self::TokenId, Self::Balance)>>,
         calldata: Option<Vec<u8>>
     ) -> DispatchResult {
         for v in id_values {
-            let (id, value) = v.as_ref();
-            Self::safe_transfer_from(from ,to, id, *value, calldata.clone())?;
+            let (id, value) = v.borrow();
+            Self::safe_transfer_from(operator, from ,to, id, *value, calldata.clone())?;
         }
 
         Ok(())
@@ -27,17 +29,21 @@ pub trait ERC1155<AccountId> {
 
     fn balance_of(owner: &AccountId, id: &Self::TokenId) -> Self::Balance;
 
+    /// Balance of every owner and id pair of `owner_ids`.
+    /// Implementations may reject batches above a maximum length
     fn balance_of_batch(
-        owner_ids: impl Iterator<Item = impl AsRef<(AccountId, Self::TokenId)>>,
-    ) -> Vec<Self::Balance> { // TODO: impl Iterator<Item = Balance>
-        owner_ids.map(|v| {
-            let (owner, id) = v.as_ref();
+        owner_ids: impl Iterator<Item = impl Borrow<(AccountId, Self::TokenId)>>,
+    ) -> Result<Vec<Self::Balance>, DispatchError> { // TODO: impl Iterator<Item = Balance>
+        Ok(owner_ids.map(|v| {
+            let (owner, id) = v.borrow();
             Self::balance_of(owner, id)
         })
-        .collect()
+        .collect())
     }
 
-    fn set_approval_for_all(owner: &AccountId, approved: bool);
+    /// Grant or revoke permission for `operator` to transfer all of `owner`'s tokens,
+    /// `owner` can't be its own operator
+    fn set_approval_for_all(owner: &AccountId, operator: &AccountId, approved: bool) -> DispatchResult;
 
     fn is_approved_for_all(owner: &AccountId, operator: &AccountId) -> bool;
 }
@@ -45,47 +51,118 @@ pub trait ERC1155<AccountId> {
 pub trait ERC1155MetadataURI<AccountId>: ERC1155<AccountId> {
     type TokenInfo: Debug + FullCodec + MaybeSerializeDeserialize + Default + Clone + PartialEq;
 
+    /// Metadata URI of `id`, `{id}` is substituted with the hex encoded token id
     fn uri(id: &Self::TokenId) -> Self::TokenInfo;
 }
 
 pub trait ERC1155MetadataURIExt<AccountId>: ERC1155MetadataURI<AccountId> {
-    fn set_uri(uri: &Self::TokenInfo);
+    /// Set the URI of `id`, or the base URI shared by all tokens when `id` is None
+    fn set_uri(id: Option<&Self::TokenId>, uri: &Self::TokenInfo) -> DispatchResult;
 }
 
 pub trait ERC1155Mintable<AccountId>: ERC1155<AccountId> {
-    fn mint(account: &AccountId, id: &Self::TokenId, amount: Self::Balance, calldata: Option<Vec<u8>>) -> Result<Self::PositiveImbalance, DispatchError>;
+    /// Issued value of a batch mint, already accounted in the issuance
+    type PositiveImbalance;
 
+    /// Mint `amount` of `id` into `account`, `operator` is the account performing the mint.
+    /// The issuance of `id` is increased accordingly
+    fn mint(operator: &AccountId, account: &AccountId, id: &Self::TokenId, amount: Self::Balance, calldata: Option<Vec<u8>>) -> DispatchResult;
+
+    /// Mint every amount of `id_amounts` into `account`, the issuance of every id is increased accordingly.
+    /// The returned imbalance records what was issued, so it can be routed to an `OnUnbalanced` handler
     fn mint_batch(
+        operator: &AccountId,
         account: &AccountId,
-        id_amounts: impl Iterator<Item = impl AsRef<(Self::TokenId, Self::Balance)>>,
+        id_amounts: impl Iterator<Item = impl Borrow<(Self::TokenId, Self::Balance)>>,
         calldata: Option<Vec<u8>>
-    ) -> DispatchResult {
-        for v in id_amounts {
-            let (id, amount) = v.as_ref();
-            Self::mint(account, id, *amount, calldata.clone())?;
-        }
-
-        Ok(())
-    }
+    ) -> Result<Self::PositiveImbalance, DispatchError>;
 }
 
 pub trait ERC1155Burnable<AccountId>: ERC1155<AccountId> {
-    fn burn(account: &AccountId, id: &Self::TokenId, amount: Self::Balance) -> Result<Self::NegativeImbalance, DispatchError>;
+    /// Destroyed value of a batch burn, already accounted in the issuance
+    type NegativeImbalance;
+
+    /// Burn `amount` of `id` from `account`, the issuance of `id` is decreased accordingly
+    fn burn(account: &AccountId, id: &Self::TokenId, amount: Self::Balance) -> DispatchResult;
 
+    /// Burn every amount of `id_amounts` from `account`, the issuance of every id is decreased accordingly.
+    /// The returned imbalance records what was destroyed, so it can be routed to an `OnUnbalanced` handler
     fn burn_batch(
         account: &AccountId,
-        id_amounts: impl Iterator<Item = impl AsRef<(Self::TokenId, Self::Balance)>>
-    ) -> DispatchResult {
-        for v in id_amounts {
-            let (id, amount) = v.as_ref();
-            Self::burn(account, id, *amount)?;
-        }
-
-        Ok(())
-    }
+        id_amounts: impl Iterator<Item = impl Borrow<(Self::TokenId, Self::Balance)>>
+    ) -> Result<Self::NegativeImbalance, DispatchError>;
 }
 
 pub trait ERC1155Reservable<AccountId>: ERC1155<AccountId> {
+    /// Move `amount` of `id` from the free balance of `owner` to the reserved balance.
+    /// Reserved balance can't be transferred or burned
     fn lock(owner: &AccountId, id: &Self::TokenId, amount: Self::Balance) -> DispatchResult;
+    /// Move `amount` of `id` from the reserved balance of `owner` back to the free balance
     fn unlock(owner: &AccountId, id: &Self::TokenId, amount: Self::Balance) -> DispatchResult;
 }
+
+/// Hook notified when an account receives tokens, the equivalent of solidity's `ERC1155TokenReceiver`.
+/// Returning an error rejects the transfer and reverts it.
+/// Calls notifying the hook are charged the weight it declares on top of their own
+pub trait ERC1155Receiver<AccountId, TokenId, Balance> {
+    /// Called after `value` of `id` has been credited to `to`
+    /// from is None when minting
+    fn on_erc1155_received(
+        operator: &AccountId,
+        from: Option<&AccountId>,
+        to: &AccountId,
+        id: &TokenId,
+        value: Balance,
+        calldata: Option<&[u8]>
+    ) -> DispatchResult;
+
+    /// Called after a batch has been credited to `to`, ids and values are in the same order
+    /// from is None when minting
+    fn on_erc1155_batch_received(
+        operator: &AccountId,
+        from: Option<&AccountId>,
+        to: &AccountId,
+        ids: &[TokenId],
+        values: &[Balance],
+        calldata: Option<&[u8]>
+    ) -> DispatchResult;
+
+    /// Upper bound of the weight of `on_erc1155_received`
+    fn on_erc1155_received_weight() -> Weight;
+
+    /// Upper bound of the weight of `on_erc1155_batch_received` for a batch of `n` entries
+    fn on_erc1155_batch_received_weight(n: u32) -> Weight;
+}
+
+/// Accept everything
+impl<AccountId, TokenId, Balance> ERC1155Receiver<AccountId, TokenId, Balance> for () {
+    fn on_erc1155_received(
+        _operator: &AccountId,
+        _from: Option<&AccountId>,
+        _to: &AccountId,
+        _id: &TokenId,
+        _value: Balance,
+        _calldata: Option<&[u8]>
+    ) -> DispatchResult {
+        Ok(())
+    }
+
+    fn on_erc1155_batch_received(
+        _operator: &AccountId,
+        _from: Option<&AccountId>,
+        _to: &AccountId,
+        _ids: &[TokenId],
+        _values: &[Balance],
+        _calldata: Option<&[u8]>
+    ) -> DispatchResult {
+        Ok(())
+    }
+
+    fn on_erc1155_received_weight() -> Weight {
+        0
+    }
+
+    fn on_erc1155_batch_received_weight(_n: u32) -> Weight {
+        0
+    }
+}
@@ -1,23 +1,41 @@
 // Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{
-    Config, Saturating,
-};
-use sp_std::{mem, result};
-use sp_runtime::{RuntimeDebug, traits::Zero};
-use frame_support::traits::{SameOrOther, Imbalance, TryDrop};
+use super::Config;
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData, mem, result};
+use sp_runtime::{RuntimeDebug, ArithmeticError, traits::{Zero, CheckedAdd, Bounded}};
+use frame_support::traits::{Get, SameOrOther, Imbalance, TryDrop};
+
+// Imbalances are bound to their token through `I`, the same `Get<TokenId>` provider as `Erc1155Token`,
+// so imbalances of different tokens can't be merged or offset against each other.
+// Positive imbalances are recorded in `PendingIssuance` while they live, so supply caps account for them
 
 /// Opaque, move-only struct with private fields that serves as a token denoting that
 /// funds have been created without any equal and opposite accounting.
 #[must_use]
 #[derive(RuntimeDebug, PartialEq, Eq)]
-pub struct PositiveImbalance<T: Config>(T::Balance, T::TokenId);
+pub struct PositiveImbalance<T: Config, I: Get<T::TokenId>>(T::Balance, PhantomData<I>);
 
-impl<T: Config> PositiveImbalance<T> {
-    /// Create a new positive imbalance from a balance.
-    pub fn new(amount: T::Balance, token: T::TokenId) -> Self {
-        PositiveImbalance(amount, token)
+impl<T: Config, I: Get<T::TokenId>> PositiveImbalance<T, I> {
+    /// Create a new positive imbalance from a balance credited ahead of the issuance.
+    pub(crate) fn new(amount: T::Balance) -> Self {
+        <super::Pallet<T>>::add_pending_issuance(&I::get(), amount);
+        PositiveImbalance(amount, PhantomData)
+    }
+
+    /// Add `amount` of an imbalance being merged into this one.
+    /// Both amounts are part of the pending issuance, which `ensure_can_issue` keeps below the maximum balance,
+    /// so the sum can't overflow. Should it anyway, the excess is settled as if its imbalance was dropped
+    fn add_amount(&mut self, amount: T::Balance) {
+        match self.0.checked_add(&amount) {
+            Some(total) => self.0 = total,
+            None => {
+                debug_assert!(false, "positive imbalance of {:?} overflows", I::get());
+                let excess = amount - (T::Balance::max_value() - self.0);
+                self.0 = T::Balance::max_value();
+                <super::Pallet<T>>::settle_pending_issuance(&I::get(), excess);
+            },
+        }
     }
 }
 
@@ -25,32 +43,47 @@ impl<T: Config> PositiveImbalance<T> {
 /// funds have been destroyed without any equal and opposite accounting.
 #[must_use]
 #[derive(RuntimeDebug, PartialEq, Eq)]
-pub struct NegativeImbalance<T: Config>(T::Balance, T::TokenId);
+pub struct NegativeImbalance<T: Config, I: Get<T::TokenId>>(T::Balance, PhantomData<I>);
 
-impl<T: Config> NegativeImbalance<T> {
+impl<T: Config, I: Get<T::TokenId>> NegativeImbalance<T, I> {
     /// Create a new negative imbalance from a balance.
-    pub fn new(amount: T::Balance, token: T::TokenId) -> Self {
-        NegativeImbalance(amount, token)
+    pub fn new(amount: T::Balance) -> Self {
+        NegativeImbalance(amount, PhantomData)
+    }
+
+    /// Add `amount` of an imbalance being merged into this one.
+    /// Both amounts were taken from balances and are still part of the issuance, which is kept below
+    /// the maximum balance, so the sum can't overflow. Should it anyway, the excess is settled as if its imbalance was dropped
+    fn add_amount(&mut self, amount: T::Balance) {
+        match self.0.checked_add(&amount) {
+            Some(total) => self.0 = total,
+            None => {
+                debug_assert!(false, "negative imbalance of {:?} overflows", I::get());
+                let excess = amount - (T::Balance::max_value() - self.0);
+                self.0 = T::Balance::max_value();
+                <super::Pallet<T>>::decrease_issuance(&I::get(), excess);
+            },
+        }
     }
 }
 
-impl<T: Config> TryDrop for PositiveImbalance<T> {
+impl<T: Config, I: Get<T::TokenId>> TryDrop for PositiveImbalance<T, I> {
     fn try_drop(self) -> result::Result<(), Self> {
         self.drop_zero()
     }
 }
 
-impl<T: Config> Default for PositiveImbalance<T> {
+impl<T: Config, I: Get<T::TokenId>> Default for PositiveImbalance<T, I> {
     fn default() -> Self {
         Self::zero()
     }
 }
 
-impl<T: Config> Imbalance<T::Balance> for PositiveImbalance<T> {
-    type Opposite = NegativeImbalance<T>;
+impl<T: Config, I: Get<T::TokenId>> Imbalance<T::Balance> for PositiveImbalance<T, I> {
+    type Opposite = NegativeImbalance<T, I>;
 
     fn zero() -> Self {
-        panic!("zero is not supposed to be used with erc1155 imbalance!")
+        Self::new(Zero::zero())
     }
 
     fn drop_zero(self) -> result::Result<(), Self> {
@@ -64,29 +97,28 @@ impl<T: Config> Imbalance<T::Balance> for PositiveImbalance<T> {
         let first = self.0.min(amount);
         let second = self.0 - first;
 
-        let token = self.1.clone();
         mem::forget(self);
-        (Self::new(first, token.clone()), Self::new(second, token))
+        (PositiveImbalance(first, PhantomData), PositiveImbalance(second, PhantomData))
     }
     fn merge(mut self, other: Self) -> Self {
-        self.0 = self.0.saturating_add(other.0);
-        mem::forget(other);
+        self.subsume(other);
 
         self
     }
     fn subsume(&mut self, other: Self) {
-        self.0 = self.0.saturating_add(other.0);
+        let amount = other.0;
         mem::forget(other);
+        self.add_amount(amount);
     }
     fn offset(self, other: Self::Opposite) -> SameOrOther<Self, Self::Opposite> {
         let (a, b) = (self.0, other.0);
-        let token = self.1.clone();
         mem::forget((self, other));
+        settle_common::<T, I>(a.min(b));
 
         if a > b {
-            SameOrOther::Same(Self::new(a - b, token))
+            SameOrOther::Same(PositiveImbalance(a - b, PhantomData))
         } else if b > a {
-            SameOrOther::Other(NegativeImbalance::new(b - a, token))
+            SameOrOther::Other(NegativeImbalance::new(b - a))
         } else {
             SameOrOther::None
         }
@@ -96,23 +128,23 @@ impl<T: Config> Imbalance<T::Balance> for PositiveImbalance<T> {
     }
 }
 
-impl<T: Config> TryDrop for NegativeImbalance<T> {
+impl<T: Config, I: Get<T::TokenId>> TryDrop for NegativeImbalance<T, I> {
     fn try_drop(self) -> result::Result<(), Self> {
         self.drop_zero()
     }
 }
 
-impl<T: Config> Default for NegativeImbalance<T> {
+impl<T: Config, I: Get<T::TokenId>> Default for NegativeImbalance<T, I> {
     fn default() -> Self {
         Self::zero()
     }
 }
 
-impl<T: Config> Imbalance<T::Balance> for NegativeImbalance<T> {
-    type Opposite = PositiveImbalance<T>;
+impl<T: Config, I: Get<T::TokenId>> Imbalance<T::Balance> for NegativeImbalance<T, I> {
+    type Opposite = PositiveImbalance<T, I>;
 
     fn zero() -> Self {
-        panic!("zero shouldn't be used with erc1155 imbalance!")
+        Self::new(Zero::zero())
     }
 
     fn drop_zero(self) -> result::Result<(), Self> {
@@ -126,29 +158,28 @@ impl<T: Config> Imbalance<T::Balance> for NegativeImbalance<T> {
         let first = self.0.min(amount);
         let second = self.0 - first;
 
-        let token = self.1.clone();
         mem::forget(self);
-        (Self::new(first, token.clone()), Self::new(second, token))
+        (Self::new(first), Self::new(second))
     }
     fn merge(mut self, other: Self) -> Self {
-        self.0 = self.0.saturating_add(other.0);
-        mem::forget(other);
+        self.subsume(other);
 
         self
     }
     fn subsume(&mut self, other: Self) {
-        self.0 = self.0.saturating_add(other.0);
+        let amount = other.0;
         mem::forget(other);
+        self.add_amount(amount);
     }
     fn offset(self, other: Self::Opposite) -> SameOrOther<Self, Self::Opposite> {
         let (a, b) = (self.0, other.0);
-        let token = self.1.clone();
         mem::forget((self, other));
+        settle_common::<T, I>(a.min(b));
 
         if a > b {
-            SameOrOther::Same(Self::new(a - b, token))
+            SameOrOther::Same(Self::new(a - b))
         } else if b > a {
-            SameOrOther::Other(Self::Opposite::new(b - a, token))
+            SameOrOther::Other(PositiveImbalance(b - a, PhantomData))
         } else {
             SameOrOther::None
         }
@@ -158,22 +189,176 @@ impl<T: Config> Imbalance<T::Balance> for NegativeImbalance<T> {
     }
 }
 
-impl<T: Config> Drop for PositiveImbalance<T> {
+/// Settle `amount` of a positive imbalance offset against as much of a negative one, as if both were dropped
+fn settle_common<T: Config, I: Get<T::TokenId>>(amount: T::Balance) {
+    <super::Pallet<T>>::settle_pending_issuance(&I::get(), amount);
+    <super::Pallet<T>>::decrease_issuance(&I::get(), amount);
+}
+
+impl<T: Config, I: Get<T::TokenId>> Drop for PositiveImbalance<T, I> {
     /// Basic drop handler will just square up the total issuance.
     fn drop(&mut self) {
-        <super::Issuance<T>>::mutate(
-            self.1,
-            |v| *v = Some(v.unwrap_or(0u32.into()).saturating_add(self.0))
-        );
+        <super::Pallet<T>>::settle_pending_issuance(&I::get(), self.0);
     }
 }
 
-impl<T: Config> Drop for NegativeImbalance<T> {
+impl<T: Config, I: Get<T::TokenId>> Drop for NegativeImbalance<T, I> {
     /// Basic drop handler will just square up the total issuance.
     fn drop(&mut self) {
-        <super::Issuance<T>>::mutate(
-            self.1,
-            |v| *v = Some(v.unwrap_or(0u32.into()).saturating_sub(self.0))
-        );
+        <super::Pallet<T>>::decrease_issuance(&I::get(), self.0);
+    }
+}
+
+/// Direction of a `MultiTokenImbalance`
+pub trait ImbalanceSign {
+    type Opposite: ImbalanceSign<Opposite = Self>;
+}
+
+/// Funds were created
+#[derive(RuntimeDebug, PartialEq, Eq)]
+pub enum Positive {}
+
+/// Funds were destroyed
+#[derive(RuntimeDebug, PartialEq, Eq)]
+pub enum Negative {}
+
+impl ImbalanceSign for Positive {
+    type Opposite = Negative;
+}
+
+impl ImbalanceSign for Negative {
+    type Opposite = Positive;
+}
+
+/// Imbalance spanning several tokens, amounts are tracked per token id so they never mix.
+/// Unlike the single token imbalances it is a settled record: the issuance of every token
+/// was already updated when it was made, so dropping it changes nothing
+#[must_use]
+#[derive(RuntimeDebug, PartialEq, Eq)]
+pub struct MultiTokenImbalance<T: Config, S: ImbalanceSign = Positive>(BTreeMap<T::TokenId, T::Balance>, PhantomData<S>);
+
+pub type PositiveMultiTokenImbalance<T> = MultiTokenImbalance<T, Positive>;
+pub type NegativeMultiTokenImbalance<T> = MultiTokenImbalance<T, Negative>;
+
+impl<T: Config, S: ImbalanceSign> MultiTokenImbalance<T, S> {
+    /// Imbalance of no token at all
+    pub fn zero() -> Self {
+        MultiTokenImbalance(BTreeMap::new(), PhantomData)
+    }
+
+    /// Imbalance of `amount` of a single token
+    pub fn new(id: T::TokenId, amount: T::Balance) -> Self {
+        let mut res = Self::zero();
+        res.add(id, amount);
+
+        res
+    }
+
+    /// Add `amount` of `id` to the imbalance.
+    /// Only used to build a record out of the aggregated amounts of a single mint or burn, where every id
+    /// is added once, so it can't overflow. The issuance was already updated, an overflow would only cut the record short
+    pub(crate) fn add(&mut self, id: T::TokenId, amount: T::Balance) {
+        if amount.is_zero() {
+            return;
+        }
+
+        let total = self.0.entry(id).or_insert(Zero::zero());
+        match total.checked_add(&amount) {
+            Some(sum) => *total = sum,
+            None => {
+                debug_assert!(false, "imbalance of {:?} overflows", id);
+                *total = T::Balance::max_value();
+            },
+        }
+    }
+
+    /// Amount of `id` held by the imbalance
+    pub fn peek(&self, id: &T::TokenId) -> T::Balance {
+        self.0.get(id).cloned().unwrap_or(Zero::zero())
+    }
+
+    /// Every token and amount held by the imbalance
+    pub fn amounts(&self) -> &BTreeMap<T::TokenId, T::Balance> {
+        &self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.values().all(|amount| amount.is_zero())
+    }
+
+    /// Drop the imbalance if it holds nothing, otherwise give it back
+    pub fn drop_zero(self) -> result::Result<(), Self> {
+        if self.is_zero() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Split off up to `amount` of `id` into the first imbalance, everything else stays in the second
+    pub fn split(mut self, id: T::TokenId, amount: T::Balance) -> (Self, Self) {
+        let held = self.peek(&id);
+        let first = held.min(amount);
+        if first == held {
+            self.0.remove(&id);
+        } else {
+            self.0.insert(id, held - first);
+        }
+
+        (Self::new(id, first), self)
+    }
+
+    /// Merge two records, fails if the amounts of a token overflow when added up
+    pub fn merge(mut self, other: Self) -> result::Result<Self, ArithmeticError> {
+        self.subsume(other)?;
+
+        Ok(self)
+    }
+
+    /// Add the amounts of `other` to this record.
+    /// Records of separate mints or burns can add up past the maximum balance, in which case nothing is added
+    pub fn subsume(&mut self, other: Self) -> result::Result<(), ArithmeticError> {
+        let mut totals = self.0.clone();
+        for (id, amount) in other.0.into_iter() {
+            let total = totals.entry(id).or_insert(Zero::zero());
+            *total = total.checked_add(&amount).ok_or(ArithmeticError::Overflow)?;
+        }
+        self.0 = totals;
+
+        Ok(())
+    }
+
+    /// Offset against an imbalance of the opposite direction, token by token.
+    /// Whatever is left of either side is returned
+    pub fn offset(
+        mut self,
+        mut other: MultiTokenImbalance<T, S::Opposite>
+    ) -> (Self, MultiTokenImbalance<T, S::Opposite>) {
+        for (id, amount) in self.0.iter_mut() {
+            let opposite = other.peek(id);
+            let common = (*amount).min(opposite);
+            if common.is_zero() {
+                continue;
+            }
+
+            *amount -= common;
+            other.0.insert(*id, opposite - common);
+        }
+        self.0.retain(|_, amount| !amount.is_zero());
+        other.0.retain(|_, amount| !amount.is_zero());
+
+        (self, other)
+    }
+}
+
+impl<T: Config, S: ImbalanceSign> TryDrop for MultiTokenImbalance<T, S> {
+    fn try_drop(self) -> result::Result<(), Self> {
+        self.drop_zero()
+    }
+}
+
+impl<T: Config, S: ImbalanceSign> Default for MultiTokenImbalance<T, S> {
+    fn default() -> Self {
+        Self::zero()
     }
 }
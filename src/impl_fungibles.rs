@@ -0,0 +1,271 @@
+//! Implementations of the `fungibles` traits, every token id is an asset
+
+use crate::{Config, Pallet, Balances, ReservedBalances, Issuance, PendingIssuance, TokenUris, UniqueTokens, Attributes, Tokens, Error, Event, erc1155::ERC1155Reservable, types::TokenDetails};
+use sp_runtime::traits::{Zero, CheckedAdd, CheckedSub, Saturating};
+use frame_support::{
+    traits::{WithdrawReasons, BalanceStatus, ExistenceRequirement, tokens::{fungibles, DepositConsequence, WithdrawConsequence}},
+    dispatch::{DispatchResult, DispatchError},
+    ensure,
+};
+
+impl<T: Config> fungibles::Inspect<T::AccountId> for Pallet<T> {
+    type AssetId = T::TokenId;
+    type Balance = T::Balance;
+
+    fn total_issuance(asset: T::TokenId) -> T::Balance {
+        <Issuance<T>>::get(asset).unwrap_or(T::Balance::zero())
+    }
+
+    fn minimum_balance(asset: T::TokenId) -> T::Balance {
+        Self::min_balance(&asset)
+    }
+
+    fn balance(asset: T::TokenId, who: &T::AccountId) -> T::Balance {
+        <Balances<T>>::get(who, asset).unwrap_or(T::Balance::zero())
+            .saturating_add(<Self as fungibles::InspectHold<T::AccountId>>::balance_on_hold(asset, who))
+    }
+
+    fn reducible_balance(asset: T::TokenId, who: &T::AccountId, keep_alive: bool) -> T::Balance {
+        let free = <Balances<T>>::get(who, asset).unwrap_or(T::Balance::zero());
+        let reducible = free.saturating_sub(Self::frozen_balance(who, &asset, WithdrawReasons::all()));
+        if keep_alive {
+            // the held balance counts towards the minimum balance, keeping alive leaves at least 1 even without one
+            let required = Self::min_balance(&asset)
+                .max(1u32.into())
+                .saturating_sub(Self::reserved_of(who, &asset));
+            reducible.min(free.saturating_sub(required))
+        } else {
+            reducible
+        }
+    }
+
+    fn can_deposit(asset: T::TokenId, who: &T::AccountId, amount: T::Balance) -> DepositConsequence {
+        let issuance = match <Issuance<T>>::get(asset) {
+            Some(issuance) => issuance,
+            None => return DepositConsequence::UnknownAsset,
+        };
+        if issuance.checked_add(&amount).is_none() {
+            return DepositConsequence::Overflow;
+        }
+        if <Balances<T>>::get(who, asset).unwrap_or(T::Balance::zero()).checked_add(&amount).is_none() {
+            return DepositConsequence::Overflow;
+        }
+        if Self::ensure_can_issue(&asset, amount).is_err() {
+            return DepositConsequence::Overflow;
+        }
+        if Self::ensure_can_receive(who, &asset, amount).is_err() {
+            return DepositConsequence::BelowMinimum;
+        }
+
+        DepositConsequence::Success
+    }
+
+    fn can_withdraw(asset: T::TokenId, who: &T::AccountId, amount: T::Balance) -> WithdrawConsequence<T::Balance> {
+        let issuance = match <Issuance<T>>::get(asset) {
+            Some(issuance) => issuance,
+            None => return WithdrawConsequence::UnknownAsset,
+        };
+        if issuance.checked_sub(&amount).is_none() {
+            return WithdrawConsequence::Underflow;
+        }
+        let new_balance = match <Balances<T>>::get(who, asset).unwrap_or(T::Balance::zero()).checked_sub(&amount) {
+            Some(new_balance) => new_balance,
+            None => return WithdrawConsequence::NoFunds,
+        };
+        if Self::ensure_can_withdraw(who, &asset, WithdrawReasons::all(), new_balance).is_err() {
+            return WithdrawConsequence::Frozen;
+        }
+        if Self::is_frozen(&asset) {
+            return WithdrawConsequence::Frozen;
+        }
+        let left = new_balance.saturating_add(Self::reserved_of(who, &asset));
+        // locked balances are never reaped
+        let locked = !Self::frozen_balance(who, &asset, WithdrawReasons::all()).is_zero();
+        if !left.is_zero() && left < Self::min_balance(&asset) && !locked {
+            return WithdrawConsequence::ReducedToZero(left);
+        }
+
+        WithdrawConsequence::Success
+    }
+}
+
+impl<T: Config> fungibles::Mutate<T::AccountId> for Pallet<T> {
+    fn mint_into(asset: T::TokenId, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        Self::can_deposit(asset, who, amount).into_result()?;
+
+        Self::do_mint(who, &asset, amount)?;
+        Self::deposit_event(Event::TransferSingle(who.clone(), None, Some(who.clone()), asset, amount));
+
+        Ok(())
+    }
+
+    fn burn_from(asset: T::TokenId, who: &T::AccountId, amount: T::Balance) -> Result<T::Balance, DispatchError> {
+        if amount.is_zero() {
+            return Ok(amount);
+        }
+        Self::can_withdraw(asset, who, amount).into_result()?;
+
+        Self::do_burn(who, &asset, amount)?;
+        Self::deposit_event(Event::TransferSingle(who.clone(), Some(who.clone()), None, asset, amount));
+
+        Ok(amount)
+    }
+}
+
+impl<T: Config> fungibles::Transfer<T::AccountId> for Pallet<T> {
+    fn transfer(
+        asset: T::TokenId,
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: T::Balance,
+        keep_alive: bool
+    ) -> Result<T::Balance, DispatchError> {
+        if amount.is_zero() || source == dest {
+            return Ok(amount);
+        }
+
+        let existence = if keep_alive { ExistenceRequirement::KeepAlive } else { ExistenceRequirement::AllowDeath };
+        Self::do_transfer(source, dest, &asset, amount, existence)?;
+        Self::deposit_event(Event::TransferSingle(source.clone(), Some(source.clone()), Some(dest.clone()), asset, amount));
+
+        Ok(amount)
+    }
+}
+
+impl<T: Config> fungibles::Unbalanced<T::AccountId> for Pallet<T> {
+    /// Set the total (free and held) balance of `who`, held balance is left untouched
+    fn set_balance(asset: T::TokenId, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        let held = <ReservedBalances<T>>::get(who, asset).unwrap_or(T::Balance::zero());
+        let free = amount.checked_sub(&held).ok_or(Error::<T>::OutOfFunds)?;
+        Self::write_balance(who, &asset, free);
+
+        Ok(())
+    }
+
+    /// Low level setter, fixed supply is not enforced here
+    fn set_total_issuance(asset: T::TokenId, amount: T::Balance) {
+        <Issuance<T>>::insert(asset, amount);
+    }
+}
+
+impl<T: Config> fungibles::InspectHold<T::AccountId> for Pallet<T> {
+    fn balance_on_hold(asset: T::TokenId, who: &T::AccountId) -> T::Balance {
+        <ReservedBalances<T>>::get(who, asset).unwrap_or(T::Balance::zero())
+    }
+
+    fn can_hold(asset: T::TokenId, who: &T::AccountId, amount: T::Balance) -> bool {
+        <Balances<T>>::get(who, asset)
+            .unwrap_or(T::Balance::zero())
+            .checked_sub(&amount)
+            .map_or(false, |new_balance| Self::ensure_can_withdraw(who, &asset, WithdrawReasons::RESERVE, new_balance).is_ok())
+    }
+}
+
+impl<T: Config> fungibles::MutateHold<T::AccountId> for Pallet<T> {
+    fn hold(asset: T::TokenId, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        Self::lock(who, &asset, amount)
+    }
+
+    fn release(
+        asset: T::TokenId,
+        who: &T::AccountId,
+        amount: T::Balance,
+        best_effort: bool
+    ) -> Result<T::Balance, DispatchError> {
+        let actual = Self::held_amount(asset, who, amount, best_effort)?;
+        Self::unlock(who, &asset, actual)?;
+
+        Ok(actual)
+    }
+
+    fn transfer_held(
+        asset: T::TokenId,
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: T::Balance,
+        best_effort: bool,
+        on_hold: bool
+    ) -> Result<T::Balance, DispatchError> {
+        let actual = Self::held_amount(asset, source, amount, best_effort)?;
+        let status = if on_hold { BalanceStatus::Reserved } else { BalanceStatus::Free };
+
+        let moved = Self::move_reserved(source, dest, &asset, actual, status)?;
+        if !moved.is_zero() && source != dest {
+            Self::deposit_event(Event::TransferSingle(source.clone(), Some(source.clone()), Some(dest.clone()), asset, moved));
+        }
+
+        Ok(moved)
+    }
+}
+
+impl<T: Config> fungibles::Create<T::AccountId> for Pallet<T> {
+    /// Register `id` with an empty supply, `admin` holds every role of the token.
+    /// The sequential ids of `create_token` are left untouched, they skip `id` once they reach it.
+    /// `is_sufficient` is not tracked by this pallet
+    fn create(
+        id: T::TokenId,
+        admin: T::AccountId,
+        _is_sufficient: bool,
+        min_balance: T::Balance
+    ) -> DispatchResult {
+        ensure!(!<Issuance<T>>::contains_key(id), Error::<T>::TokenAlreadyExists);
+
+        <Tokens<T>>::insert(id, TokenDetails::new(admin, None, false, min_balance));
+        <Issuance<T>>::insert(id, T::Balance::zero());
+
+        Ok(())
+    }
+}
+
+impl<T: Config> fungibles::Destroy<T::AccountId> for Pallet<T> {
+    /// Supply of the token, only tokens with no supply left can be destroyed
+    type DestroyWitness = T::Balance;
+
+    fn get_destroy_witness(id: &T::TokenId) -> Option<T::Balance> {
+        <Issuance<T>>::get(id)
+    }
+
+    fn destroy(
+        id: T::TokenId,
+        witness: T::Balance,
+        maybe_check_owner: Option<T::AccountId>
+    ) -> Result<T::Balance, DispatchError> {
+        let issuance = <Issuance<T>>::get(id).ok_or(Error::<T>::TokenNotFound)?;
+        if let Some(owner) = maybe_check_owner {
+            let details = <Tokens<T>>::get(id).ok_or(Error::<T>::NoPermission)?;
+            ensure!(owner == details.admin, Error::<T>::NoPermission);
+        }
+        ensure!(issuance.is_zero() && witness.is_zero(), Error::<T>::InUse);
+        // balance credited by positive imbalances still alive
+        ensure!(<PendingIssuance<T>>::get(id).is_zero(), Error::<T>::InUse);
+
+        <Issuance<T>>::remove(id);
+        <Tokens<T>>::remove(id);
+        <TokenUris<T>>::remove(id);
+        <UniqueTokens<T>>::remove(id);
+        let _ = <Attributes<T>>::remove_prefix(id, None);
+
+        Ok(issuance)
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Amount of the held balance of `who` to act on, all of `amount` unless `best_effort`
+    fn held_amount(
+        asset: T::TokenId,
+        who: &T::AccountId,
+        amount: T::Balance,
+        best_effort: bool
+    ) -> Result<T::Balance, DispatchError> {
+        let held = <ReservedBalances<T>>::get(who, asset).unwrap_or(T::Balance::zero());
+        if best_effort {
+            Ok(amount.min(held))
+        } else {
+            ensure!(held >= amount, Error::<T>::InsufficientReserve);
+            Ok(amount)
+        }
+    }
+}
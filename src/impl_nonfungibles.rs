@@ -0,0 +1,95 @@
+//! Implementations of the `nonfungibles` traits for unique tokens.
+//! The pallet is a single class, every unique token id is an instance
+
+use crate::{Config, Pallet, Balances, ReservedBalances, UniqueTokens, Owners, Attributes, Error, Event};
+use sp_std::{boxed::Box, collections::btree_set::BTreeSet, convert::TryInto, vec::Vec};
+use frame_support::{
+    traits::{ExistenceRequirement, tokens::nonfungibles},
+    dispatch::DispatchResult,
+    ensure, BoundedVec,
+};
+
+impl<T: Config> nonfungibles::Inspect<T::AccountId> for Pallet<T> {
+    type InstanceId = T::TokenId;
+    type ClassId = ();
+
+    fn owner(_class: &(), instance: &T::TokenId) -> Option<T::AccountId> {
+        <Owners<T>>::get(instance)
+    }
+
+    fn attribute(_class: &(), instance: &T::TokenId, key: &[u8]) -> Option<Vec<u8>> {
+        let key: BoundedVec<u8, T::StringLimit> = key.to_vec().try_into().ok()?;
+        <Attributes<T>>::get(instance, key).map(|value| value.into_inner())
+    }
+
+    fn can_transfer(_class: &(), instance: &T::TokenId) -> bool {
+        <Owners<T>>::contains_key(instance) && !Self::is_frozen(instance)
+    }
+}
+
+impl<T: Config> nonfungibles::InspectEnumerable<T::AccountId> for Pallet<T> {
+    fn classes() -> Box<dyn Iterator<Item = ()>> {
+        Box::new(sp_std::iter::once(()))
+    }
+
+    fn instances(_class: &()) -> Box<dyn Iterator<Item = T::TokenId>> {
+        Box::new(<Owners<T>>::iter().map(|(id, _)| id))
+    }
+
+    fn owned(who: &T::AccountId) -> Box<dyn Iterator<Item = ((), T::TokenId)>> {
+        Box::new(Self::owned_in_class(&(), who).map(|id| ((), id)))
+    }
+
+    fn owned_in_class(_class: &(), who: &T::AccountId) -> Box<dyn Iterator<Item = T::TokenId>> {
+        // a unique token may be held entirely as free or reserved balance
+        let held: BTreeSet<T::TokenId> = <Balances<T>>::iter_prefix(who).map(|(id, _)| id)
+            .chain(<ReservedBalances<T>>::iter_prefix(who).map(|(id, _)| id))
+            .filter(|id| <UniqueTokens<T>>::get(id))
+            .collect();
+        let who = who.clone();
+
+        Box::new(held.into_iter().filter(move |id| <Owners<T>>::get(id).as_ref() == Some(&who)))
+    }
+}
+
+impl<T: Config> nonfungibles::Mutate<T::AccountId> for Pallet<T> {
+    fn mint_into(_class: &(), instance: &T::TokenId, who: &T::AccountId) -> DispatchResult {
+        Self::do_create_unique(*instance, who.clone(), who)
+    }
+
+    fn burn_from(_class: &(), instance: &T::TokenId) -> DispatchResult {
+        ensure!(<UniqueTokens<T>>::get(instance), Error::<T>::NotUnique);
+        let owner = <Owners<T>>::get(instance).ok_or(Error::<T>::TokenNotFound)?;
+
+        Self::do_burn(&owner, instance, 1u32.into())?;
+        Self::deposit_event(Event::TransferSingle(owner.clone(), Some(owner), None, *instance, 1u32.into()));
+
+        Ok(())
+    }
+
+    fn set_attribute(_class: &(), instance: &T::TokenId, key: &[u8], value: &[u8]) -> DispatchResult {
+        ensure!(<UniqueTokens<T>>::get(instance), Error::<T>::NotUnique);
+        let key: BoundedVec<u8, T::StringLimit> = key.to_vec().try_into()
+            .map_err(|_| Error::<T>::BadMetadata)?;
+        let value: BoundedVec<u8, T::StringLimit> = value.to_vec().try_into()
+            .map_err(|_| Error::<T>::BadMetadata)?;
+
+        <Attributes<T>>::insert(instance, key, value);
+        Ok(())
+    }
+}
+
+impl<T: Config> nonfungibles::Transfer<T::AccountId> for Pallet<T> {
+    fn transfer(_class: &(), instance: &T::TokenId, destination: &T::AccountId) -> DispatchResult {
+        ensure!(<UniqueTokens<T>>::get(instance), Error::<T>::NotUnique);
+        let owner = <Owners<T>>::get(instance).ok_or(Error::<T>::TokenNotFound)?;
+        if owner == *destination {
+            return Ok(());
+        }
+
+        Self::do_transfer(&owner, destination, instance, 1u32.into(), ExistenceRequirement::AllowDeath)?;
+        Self::deposit_event(Event::TransferSingle(owner.clone(), Some(owner), Some(destination.clone()), *instance, 1u32.into()));
+
+        Ok(())
+    }
+}
@@ -1,20 +1,33 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub(crate) mod imbalance;
+pub mod imbalance;
 pub mod weights;
 pub mod token;
 pub mod erc1155;
+pub mod types;
+mod impl_fungibles;
+mod impl_nonfungibles;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+#[cfg(test)]
+mod proptests;
 
 pub use pallet::*;
 use erc1155::*;
+use types::*;
 
 use codec::{Codec};
 use sp_std::fmt::Debug;
 use weights::WeightInfo;
-use frame_support::{dispatch::{DispatchResult, DispatchError}, ensure};
+use frame_support::{dispatch::{DispatchResult, DispatchError}, ensure, transactional, traits::{WithdrawReasons, BalanceStatus, ExistenceRequirement, OnUnbalanced}, BoundedVec, WeakBoundedVec};
 use frame_system::{pallet_prelude::BlockNumberFor};
-use sp_runtime::traits::{Saturating, AtLeast32BitUnsigned, StaticLookup, Zero, CheckedSub};
-use sp_std::vec::Vec;
+use sp_runtime::traits::{Saturating, AtLeast32BitUnsigned, StaticLookup, Zero, CheckedSub, CheckedAdd, UniqueSaturatedInto, Bounded};
+use sp_std::{borrow::Borrow, collections::{btree_map::BTreeMap, btree_set::BTreeSet}, convert::{TryFrom, TryInto}, vec::Vec};
 
 
 #[frame_support::pallet]
@@ -22,6 +35,7 @@ pub mod pallet {
     use super::*;
 	use frame_support::{
         pallet_prelude::*,
+        traits::EnsureOrigin,
     };
     use frame_system::pallet_prelude::*;
 
@@ -36,6 +50,41 @@ pub mod pallet {
 
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
+        /// Origin allowed to create and mint tokens, the resulting account is used as the operator
+        type CreateOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+
+        /// Origin allowed to change token metadata
+        type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+        /// Maximum length of a stored attribute key or value
+        #[pallet::constant]
+        type StringLimit: Get<u32>;
+
+        /// Bounded type a metadata URI is stored as, `BoundedVec<u8, StringLimit>` for instance
+        type TokenInfo: Parameter + Member + MaxEncodedLen + TryFrom<Vec<u8>> + Into<Vec<u8>>;
+
+        /// Identifier of named reserves made through `Erc1155Token`
+        type ReserveIdentifier: Parameter + Member + MaxEncodedLen + Ord + Copy;
+
+        /// Maximum number of named reserves per account and token
+        #[pallet::constant]
+        type MaxReserves: Get<u32>;
+
+        /// Maximum number of locks per account and token
+        #[pallet::constant]
+        type MaxLocks: Get<u32>;
+
+        /// Maximum number of entries in a batch call
+        #[pallet::constant]
+        type MaxBatchSize: Get<u32>;
+
+        /// Notified whenever an account receives tokens, may reject the transfer
+        type Receiver: ERC1155Receiver<Self::AccountId, Self::TokenId, Self::Balance>;
+
+        /// Handler for the balance left when an account falls below the minimum balance of a token,
+        /// the issuance is already reduced by the dust
+        type DustRemoval: OnUnbalanced<imbalance::NegativeMultiTokenImbalance<Self>>;
+
         type WeightInfo: WeightInfo;
 	}
 
@@ -47,10 +96,70 @@ pub mod pallet {
     #[pallet::getter(fn issuance)]
     pub type Issuance<T: Config> = StorageMap<_, Twox64Concat, T::TokenId, T::Balance>;
 
+    /// Balance credited through `Erc1155Token` ahead of the issuance, added to it once the positive imbalances are dropped
+    #[pallet::storage]
+    pub type PendingIssuance<T: Config> = StorageMap<_, Twox64Concat, T::TokenId, T::Balance, ValueQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn balance_of)]
     pub type Balances<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, T::TokenId, T::Balance>;
 
+    /// Balance set aside through `ERC1155Reservable::lock`, not spendable until unlocked
+    #[pallet::storage]
+    #[pallet::getter(fn reserved_balance_of)]
+    pub type ReservedBalances<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, T::TokenId, T::Balance>;
+
+    /// Named reserves, every entry is also accounted in ReservedBalances
+    #[pallet::storage]
+    pub type NamedReserves<T: Config> = StorageDoubleMap<
+        _, Blake2_128Concat, T::AccountId, Twox64Concat, T::TokenId,
+        BoundedVec<ReserveData<T::ReserveIdentifier, T::Balance>, T::MaxReserves>,
+        ValueQuery
+    >;
+
+    /// Locks on the free balance set through `Erc1155Token`'s `LockableCurrency`
+    #[pallet::storage]
+    pub type Locks<T: Config> = StorageDoubleMap<
+        _, Blake2_128Concat, T::AccountId, Twox64Concat, T::TokenId,
+        WeakBoundedVec<BalanceLock<T::Balance>, T::MaxLocks>,
+        ValueQuery
+    >;
+
+    /// Roles and state of tokens
+    #[pallet::storage]
+    #[pallet::getter(fn token_details)]
+    pub type Tokens<T: Config> = StorageMap<_, Twox64Concat, T::TokenId, TokenDetails<T::AccountId, T::Balance>>;
+
+    /// Tokens with a supply of at most one, usable through the `nonfungibles` traits
+    #[pallet::storage]
+    #[pallet::getter(fn is_unique)]
+    pub type UniqueTokens<T: Config> = StorageMap<_, Twox64Concat, T::TokenId, bool, ValueQuery>;
+
+    /// Current holder of every unique token
+    #[pallet::storage]
+    #[pallet::getter(fn owner_of)]
+    pub type Owners<T: Config> = StorageMap<_, Twox64Concat, T::TokenId, T::AccountId>;
+
+    /// Attributes of unique tokens
+    #[pallet::storage]
+    pub type Attributes<T: Config> = StorageDoubleMap<
+        _, Twox64Concat, T::TokenId, Blake2_128Concat, BoundedVec<u8, T::StringLimit>,
+        BoundedVec<u8, T::StringLimit>
+    >;
+
+    /// owner -> operator -> approved
+    #[pallet::storage]
+    #[pallet::getter(fn operator_approvals)]
+    pub type OperatorApprovals<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, bool, ValueQuery>;
+
+    /// URI shared by every token without an override
+    #[pallet::storage]
+    pub type BaseUri<T: Config> = StorageValue<_, T::TokenInfo>;
+
+    /// Per token URI overriding the base URI
+    #[pallet::storage]
+    pub type TokenUris<T: Config> = StorageMap<_, Twox64Concat, T::TokenId, T::TokenInfo>;
+
     #[pallet::storage]
     pub type LastTokenId<T: Config> = StorageValue<_, T::TokenId>;
 
@@ -62,15 +171,105 @@ pub mod pallet {
         /// from is None when minting
         /// to is None when burning
         ///
-        /// from, to, token_id, value
-        TransferSingle(Option<T::AccountId>, Option<T::AccountId>, T::TokenId, T::Balance)
+        /// operator, from, to, token_id, value
+        TransferSingle(T::AccountId, Option<T::AccountId>, Option<T::AccountId>, T::TokenId, T::Balance),
+        /// Batch transfer event, token_ids and values are in the same order
+        /// from is None when minting
+        /// to is None when burning
+        ///
+        /// operator, from, to, token_ids, values
+        TransferBatch(T::AccountId, Option<T::AccountId>, Option<T::AccountId>, Vec<T::TokenId>, Vec<T::Balance>),
+        /// Operator approval was granted or revoked
+        ///
+        /// owner, operator, approved
+        ApprovalForAll(T::AccountId, T::AccountId, bool),
+        /// Metadata URI of a token changed
+        ///
+        /// uri, token_id
+        URI(Vec<u8>, T::TokenId),
+        /// Base metadata URI changed
+        ///
+        /// uri
+        BaseURI(Vec<u8>),
+        /// Free balance was moved to the reserved balance
+        ///
+        /// who, token_id, value
+        Reserved(T::AccountId, T::TokenId, T::Balance),
+        /// Reserved balance was moved back to the free balance
+        ///
+        /// who, token_id, value
+        Unreserved(T::AccountId, T::TokenId, T::Balance),
+        /// Admin of a token changed
+        ///
+        /// token_id, admin
+        OwnerChanged(T::TokenId, T::AccountId),
+        /// Roles of a token changed
+        ///
+        /// token_id, issuer, admin, freezer
+        TeamChanged(T::TokenId, T::AccountId, T::AccountId, T::AccountId),
+        /// Transfers of a token were disabled
+        ///
+        /// token_id
+        Frozen(T::TokenId),
+        /// Transfers of a token were enabled again
+        ///
+        /// token_id
+        Thawed(T::TokenId),
+        /// Tokens were created, the issuance grew by value
+        ///
+        /// token_id, to, value
+        Minted(T::TokenId, T::AccountId, T::Balance),
+        /// Tokens were destroyed, the issuance shrank by value
+        ///
+        /// token_id, from, value
+        Burned(T::TokenId, T::AccountId, T::Balance),
+        /// Balance of an account fell below the minimum balance of the token and was removed
+        ///
+        /// who, token_id, value
+        DustLost(T::AccountId, T::TokenId, T::Balance)
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
         TokenNotFound,
         OutOfFunds,
-        AccountNotFound
+        AccountNotFound,
+        /// Caller is neither the owner nor an approved operator
+        NotApproved,
+        /// URI doesn't fit in `TokenInfo`, or attribute is longer than `StringLimit`
+        BadMetadata,
+        /// Reserved balance is lower than the requested amount
+        InsufficientReserve,
+        /// Account already has MaxReserves named reserves for the token
+        TooManyReserves,
+        /// Free balance would drop below a lock
+        LiquidityRestrictions,
+        /// A token with this id already exists
+        TokenAlreadyExists,
+        /// Token still has a non zero issuance
+        InUse,
+        /// Minting would exceed the supply allowed for the token
+        SupplyCapExceeded,
+        /// Token is not unique
+        NotUnique,
+        /// Caller doesn't hold the role required for the operation
+        NoPermission,
+        /// Token is frozen
+        Frozen,
+        /// Issuance of the token can't change
+        FixedSupply,
+        /// Balance or issuance would overflow
+        Overflow,
+        /// Balance would be below the minimum balance of the token
+        BelowMinimum,
+        /// Transfer would remove the balance of an account that must be kept alive
+        KeepAlive,
+        /// Every token id is in use
+        NoAvailableTokenId,
+        /// Batch has more entries than `MaxBatchSize`
+        BatchTooLarge,
+        /// Account can't approve itself as an operator
+        SelfApproval
 	}
 
 	#[pallet::hooks]
@@ -78,7 +277,7 @@ pub mod pallet {
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-        #[pallet::weight(T::WeightInfo::transfer())]
+        #[pallet::weight(T::WeightInfo::safe_transfer().saturating_add(T::Receiver::on_erc1155_received_weight()))]
         pub fn safe_transfer(
             from: OriginFor<T>,
             to: <T::Lookup as StaticLookup>::Source,
@@ -88,7 +287,272 @@ pub mod pallet {
             let sender = ensure_signed(from)?;
             let recv = T::Lookup::lookup(to)?;
 
-            Self::safe_transfer_from(&sender, &recv, &token_id, value, None)?;
+            Self::safe_transfer_from(&sender, &sender, &recv, &token_id, value, None)?;
+            Ok(().into())
+        }
+
+        /// Transfer a batch of tokens, the storage of duplicate ids is charged once
+        #[pallet::weight(
+            T::WeightInfo::safe_batch_transfer(id_values.len() as u32, id_values.len() as u32)
+                .saturating_add(T::Receiver::on_erc1155_batch_received_weight(id_values.len() as u32))
+        )]
+        pub fn safe_batch_transfer(
+            from: OriginFor<T>,
+            to: <T::Lookup as StaticLookup>::Source,
+            id_values: Vec<(T::TokenId, T::Balance)>
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(from)?;
+            let recv = T::Lookup::lookup(to)?;
+            Self::ensure_batch_size(id_values.len())?;
+
+            let len = id_values.len() as u32;
+            let distinct = Self::distinct_ids(&id_values);
+            Self::safe_batch_transfer_from(&sender, &sender, &recv, id_values.into_iter(), None)?;
+            let weight = T::WeightInfo::safe_batch_transfer(len, distinct)
+                .saturating_add(T::Receiver::on_erc1155_batch_received_weight(len));
+            Ok(Some(weight).into())
+        }
+
+        #[pallet::weight(T::WeightInfo::set_approval_for_all())]
+        pub fn set_approval_for_all(
+            origin: OriginFor<T>,
+            operator: <T::Lookup as StaticLookup>::Source,
+            approved: bool
+        ) -> DispatchResultWithPostInfo {
+            let owner = ensure_signed(origin)?;
+            let operator = T::Lookup::lookup(operator)?;
+
+            <Self as ERC1155<T::AccountId>>::set_approval_for_all(&owner, &operator, approved)?;
+            Ok(().into())
+        }
+
+        /// Transfer on behalf of `from`, the caller must be an approved operator
+        #[pallet::weight(T::WeightInfo::transfer_from().saturating_add(T::Receiver::on_erc1155_received_weight()))]
+        pub fn transfer_from(
+            origin: OriginFor<T>,
+            from: <T::Lookup as StaticLookup>::Source,
+            to: <T::Lookup as StaticLookup>::Source,
+            token_id: T::TokenId,
+            #[pallet::compact] value: T::Balance
+        ) -> DispatchResultWithPostInfo {
+            let operator = ensure_signed(origin)?;
+            let from = T::Lookup::lookup(from)?;
+            let recv = T::Lookup::lookup(to)?;
+
+            Self::safe_transfer_from(&operator, &from, &recv, &token_id, value, None)?;
+            Ok(().into())
+        }
+
+        #[pallet::weight(T::WeightInfo::burn())]
+        pub fn burn(
+            origin: OriginFor<T>,
+            token_id: T::TokenId,
+            #[pallet::compact] value: T::Balance
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+
+            <Self as ERC1155Burnable<T::AccountId>>::burn(&sender, &token_id, value)?;
+            Ok(().into())
+        }
+
+        /// Burn tokens from any account, the caller must be the admin of the token
+        #[pallet::weight(T::WeightInfo::admin_burn())]
+        pub fn admin_burn(
+            origin: OriginFor<T>,
+            who: <T::Lookup as StaticLookup>::Source,
+            token_id: T::TokenId,
+            #[pallet::compact] value: T::Balance
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            let details = Self::details(&token_id)?;
+            ensure!(sender == details.admin, Error::<T>::NoPermission);
+
+            Self::do_burn(&who, &token_id, value)?;
+            if !value.is_zero() {
+                Self::deposit_event(Event::TransferSingle(sender, Some(who), None, token_id, value));
+            }
+            Ok(().into())
+        }
+
+        /// Burn a batch of the caller's tokens, the storage of duplicate ids is charged once
+        #[pallet::weight(T::WeightInfo::burn_batch(id_values.len() as u32, id_values.len() as u32))]
+        pub fn burn_batch(
+            origin: OriginFor<T>,
+            id_values: Vec<(T::TokenId, T::Balance)>
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_batch_size(id_values.len())?;
+
+            let len = id_values.len() as u32;
+            let distinct = Self::distinct_ids(&id_values);
+            let _ = <Self as ERC1155Burnable<T::AccountId>>::burn_batch(&sender, id_values.into_iter())?;
+            Ok(Some(T::WeightInfo::burn_batch(len, distinct)).into())
+        }
+
+        /// Create a token crediting `initial_supply` to `owner`.
+        /// Issuance can never exceed `max_supply`, and never changes at all with `fixed_supply`.
+        /// Balances below `min_balance` are removed as dust
+        #[pallet::weight(T::WeightInfo::create_token())]
+        pub fn create_token(
+            origin: OriginFor<T>,
+            owner: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] initial_supply: T::Balance,
+            max_supply: Option<T::Balance>,
+            fixed_supply: bool,
+            #[pallet::compact] min_balance: T::Balance
+        ) -> DispatchResultWithPostInfo {
+            let creator = T::CreateOrigin::ensure_origin(origin)?;
+            let owner = T::Lookup::lookup(owner)?;
+
+            Self::do_create_token(creator, owner, initial_supply, max_supply, fixed_supply, min_balance)?;
+            Ok(().into())
+        }
+
+        /// Create a token with a supply of one owned by `owner`
+        #[pallet::weight(T::WeightInfo::create_unique_token())]
+        pub fn create_unique_token(
+            origin: OriginFor<T>,
+            owner: <T::Lookup as StaticLookup>::Source
+        ) -> DispatchResultWithPostInfo {
+            let creator = T::CreateOrigin::ensure_origin(origin)?;
+            let owner = T::Lookup::lookup(owner)?;
+
+            let id = Self::free_token_id()?;
+            Self::take_token_id(id)?;
+            Self::do_create_unique(id, creator, &owner)?;
+            Ok(().into())
+        }
+
+        /// Mint new tokens, the caller must be the issuer or the admin of the token
+        #[pallet::weight(T::WeightInfo::mint().saturating_add(T::Receiver::on_erc1155_received_weight()))]
+        pub fn mint(
+            origin: OriginFor<T>,
+            to: <T::Lookup as StaticLookup>::Source,
+            token_id: T::TokenId,
+            #[pallet::compact] value: T::Balance
+        ) -> DispatchResultWithPostInfo {
+            let operator = ensure_signed(origin)?;
+            let recv = T::Lookup::lookup(to)?;
+            Self::ensure_issuer(&operator, &token_id)?;
+
+            <Self as ERC1155Mintable<T::AccountId>>::mint(&operator, &recv, &token_id, value, None)?;
+            Ok(().into())
+        }
+
+        /// Mint a batch of tokens, the caller must be the issuer or the admin of every token.
+        /// The storage of duplicate ids is charged once
+        #[pallet::weight(
+            T::WeightInfo::mint_batch(id_values.len() as u32, id_values.len() as u32)
+                .saturating_add(T::Receiver::on_erc1155_batch_received_weight(id_values.len() as u32))
+        )]
+        pub fn mint_batch(
+            origin: OriginFor<T>,
+            to: <T::Lookup as StaticLookup>::Source,
+            id_values: Vec<(T::TokenId, T::Balance)>
+        ) -> DispatchResultWithPostInfo {
+            let operator = ensure_signed(origin)?;
+            let recv = T::Lookup::lookup(to)?;
+            Self::ensure_batch_size(id_values.len())?;
+            for (token_id, _) in id_values.iter() {
+                Self::ensure_issuer(&operator, token_id)?;
+            }
+
+            let len = id_values.len() as u32;
+            let distinct = Self::distinct_ids(&id_values);
+            let _ = <Self as ERC1155Mintable<T::AccountId>>::mint_batch(&operator, &recv, id_values.into_iter(), None)?;
+            let weight = T::WeightInfo::mint_batch(len, distinct)
+                .saturating_add(T::Receiver::on_erc1155_batch_received_weight(len));
+            Ok(Some(weight).into())
+        }
+
+        /// Set the metadata URI of `token_id`, or the base URI when `token_id` is None.
+        /// An empty URI removes a token override
+        #[pallet::weight(T::WeightInfo::set_uri())]
+        pub fn set_uri(
+            origin: OriginFor<T>,
+            token_id: Option<T::TokenId>,
+            uri: Vec<u8>
+        ) -> DispatchResultWithPostInfo {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            <Self as ERC1155MetadataURIExt<T::AccountId>>::set_uri(token_id.as_ref(), &uri)?;
+            Ok(().into())
+        }
+
+        /// Hand the admin role of a token to `new_admin`, the caller must be the current admin
+        #[pallet::weight(T::WeightInfo::transfer_ownership())]
+        pub fn transfer_ownership(
+            origin: OriginFor<T>,
+            token_id: T::TokenId,
+            new_admin: <T::Lookup as StaticLookup>::Source
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            let new_admin = T::Lookup::lookup(new_admin)?;
+
+            <Tokens<T>>::try_mutate(token_id, |details| -> DispatchResult {
+                let details = details.as_mut().ok_or(Error::<T>::TokenNotFound)?;
+                ensure!(sender == details.admin, Error::<T>::NoPermission);
+
+                details.admin = new_admin.clone();
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::OwnerChanged(token_id, new_admin));
+            Ok(().into())
+        }
+
+        /// Change the issuer, admin and freezer of a token, the caller must be the current admin
+        #[pallet::weight(T::WeightInfo::set_team())]
+        pub fn set_team(
+            origin: OriginFor<T>,
+            token_id: T::TokenId,
+            issuer: <T::Lookup as StaticLookup>::Source,
+            admin: <T::Lookup as StaticLookup>::Source,
+            freezer: <T::Lookup as StaticLookup>::Source
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            let issuer = T::Lookup::lookup(issuer)?;
+            let admin = T::Lookup::lookup(admin)?;
+            let freezer = T::Lookup::lookup(freezer)?;
+
+            <Tokens<T>>::try_mutate(token_id, |details| -> DispatchResult {
+                let details = details.as_mut().ok_or(Error::<T>::TokenNotFound)?;
+                ensure!(sender == details.admin, Error::<T>::NoPermission);
+
+                details.issuer = issuer.clone();
+                details.admin = admin.clone();
+                details.freezer = freezer.clone();
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::TeamChanged(token_id, issuer, admin, freezer));
+            Ok(().into())
+        }
+
+        /// Disable transfers and burns of a token, the caller must be the freezer or the admin
+        #[pallet::weight(T::WeightInfo::freeze())]
+        pub fn freeze(
+            origin: OriginFor<T>,
+            token_id: T::TokenId
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+
+            Self::set_frozen(&sender, &token_id, true)?;
+            Self::deposit_event(Event::Frozen(token_id));
+            Ok(().into())
+        }
+
+        /// Enable transfers and burns of a token again, the caller must be the freezer or the admin
+        #[pallet::weight(T::WeightInfo::thaw())]
+        pub fn thaw(
+            origin: OriginFor<T>,
+            token_id: T::TokenId
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+
+            Self::set_frozen(&sender, &token_id, false)?;
+            Self::deposit_event(Event::Thawed(token_id));
             Ok(().into())
         }
 	}
@@ -96,7 +560,19 @@ pub mod pallet {
     /// Genesis config
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
+        /// Token existing from genesis with no supply, usable right away through `Erc1155Token`.
+        /// It has no roles unless also declared in `tokens`. Sequential ids start after it and
+        /// every declared token, so tokens created later never take its id
         pub initial_token: T::TokenId,
+        /// Tokens created at genesis: id, admin, max supply, min balance and metadata URI.
+        /// The admin holds every role of the token, an empty URI sets none
+        pub tokens: Vec<(T::TokenId, T::AccountId, Option<T::Balance>, T::Balance, Vec<u8>)>,
+        /// Initial balances: account, id and amount. Issuance is the sum of the balances of every id
+        pub balances: Vec<(T::AccountId, T::TokenId, T::Balance)>,
+        /// Operator approvals: owner and operator
+        pub approvals: Vec<(T::AccountId, T::AccountId)>,
+        /// Base URI shared by all tokens, empty sets none
+        pub base_uri: Vec<u8>,
     }
 
     #[cfg(feature = "std")]
@@ -104,6 +580,10 @@ pub mod pallet {
         fn default() -> Self {
             Self {
                 initial_token: 0u32.into(),
+                tokens: Vec::new(),
+                balances: Vec::new(),
+                approvals: Vec::new(),
+                base_uri: Vec::new(),
             }
         }
     }
@@ -113,19 +593,110 @@ pub mod pallet {
     impl<T: Config> GenesisBuild<T> for GenesisConfig<T>
     {
         fn build(&self) {
-            <LastTokenId<T>>::put(self.initial_token);
-            <Issuance<T>>::insert(self.initial_token, T::Balance::from(0u32))
+            <Issuance<T>>::insert(self.initial_token, T::Balance::from(0u32));
+            let mut last_token = self.initial_token;
+
+            for (id, admin, max_supply, min_balance, uri) in self.tokens.iter() {
+                assert!(!<Tokens<T>>::contains_key(id), "token {:?} is declared twice", id);
+
+                <Tokens<T>>::insert(id, TokenDetails::new(admin.clone(), *max_supply, false, *min_balance));
+                <Issuance<T>>::insert(id, T::Balance::from(0u32));
+                if !uri.is_empty() {
+                    let uri: T::TokenInfo = uri.clone().try_into().ok()
+                        .expect("token URI doesn't fit in TokenInfo");
+                    <TokenUris<T>>::insert(id, uri);
+                }
+                last_token = last_token.max(id.saturating_add(1u32.into()));
+            }
+            <LastTokenId<T>>::put(last_token);
+
+            for (who, id, amount) in self.balances.iter() {
+                assert!(<Issuance<T>>::contains_key(id), "balance of undeclared token {:?}", id);
+                assert!(!<Balances<T>>::contains_key(who, id), "balance of {:?} in {:?} is declared twice", who, id);
+                assert!(*amount >= Pallet::<T>::min_balance(id), "balance of {:?} in {:?} is below the minimum", who, id);
+
+                let issuance = <Issuance<T>>::get(id).unwrap_or(T::Balance::zero())
+                    .checked_add(amount)
+                    .expect("issuance overflows");
+                if let Some(max) = <Tokens<T>>::get(id).and_then(|details| details.max_supply) {
+                    assert!(issuance <= max, "issuance of {:?} exceeds the max supply", id);
+                }
+
+                Pallet::<T>::write_balance(who, id, *amount);
+                <Issuance<T>>::insert(id, issuance);
+            }
+
+            for (owner, operator) in self.approvals.iter() {
+                assert!(owner != operator, "{:?} approves itself", owner);
+                <OperatorApprovals<T>>::insert(owner, operator, true);
+            }
+
+            if !self.base_uri.is_empty() {
+                let uri: T::TokenInfo = self.base_uri.clone().try_into().ok()
+                    .expect("base URI doesn't fit in TokenInfo");
+                <BaseUri<T>>::put(uri);
+            }
         }
     }
 }
 
 impl<T: Config> pallet::Pallet<T> {
-    pub fn create_token(account: T::AccountId, initial_supply: T::Balance) -> T::TokenId {
-        let token = Self::token_inc();
-        <Balances<T>>::insert(account, token, initial_supply);
+    /// Create a token with `initial_supply` credited to `account` under the first free id,
+    /// `creator` holds every role of the token
+    pub fn do_create_token(
+        creator: T::AccountId,
+        account: T::AccountId,
+        initial_supply: T::Balance,
+        max_supply: Option<T::Balance>,
+        fixed_supply: bool,
+        min_balance: T::Balance
+    ) -> Result<T::TokenId, DispatchError> {
+        // ids with an issuance, like the genesis token, are skipped so no role is granted over existing supply
+        let token = Self::free_token_id()?;
+
+        if let Some(max) = max_supply {
+            ensure!(initial_supply <= max, Error::<T>::SupplyCapExceeded);
+        }
+        ensure!(initial_supply.is_zero() || initial_supply >= min_balance, Error::<T>::BelowMinimum);
+
+        Self::take_token_id(token)?;
+        <Tokens<T>>::insert(token, TokenDetails::new(creator.clone(), max_supply, fixed_supply, min_balance));
+        Self::write_balance(&account, &token, initial_supply);
         <Issuance<T>>::insert(token, initial_supply);
 
-        return token;
+        if !initial_supply.is_zero() {
+            Self::deposit_event(Event::Minted(token, account.clone(), initial_supply));
+        }
+        Self::deposit_event(Event::TransferSingle(creator, None, Some(account), token, initial_supply));
+
+        Ok(token)
+    }
+
+    fn details(id: &T::TokenId) -> Result<TokenDetails<T::AccountId, T::Balance>, DispatchError> {
+        <Tokens<T>>::get(id).ok_or(Error::<T>::TokenNotFound.into())
+    }
+
+    /// Ensure `who` may mint `id`
+    fn ensure_issuer(who: &T::AccountId, id: &T::TokenId) -> DispatchResult {
+        let details = Self::details(id)?;
+        ensure!(*who == details.issuer || *who == details.admin, Error::<T>::NoPermission);
+
+        Ok(())
+    }
+
+    /// Freeze or thaw `id` on behalf of `who`
+    fn set_frozen(who: &T::AccountId, id: &T::TokenId, frozen: bool) -> DispatchResult {
+        <Tokens<T>>::try_mutate(id, |details| -> DispatchResult {
+            let details = details.as_mut().ok_or(Error::<T>::TokenNotFound)?;
+            ensure!(*who == details.freezer || *who == details.admin, Error::<T>::NoPermission);
+
+            details.is_frozen = frozen;
+            Ok(())
+        })
+    }
+
+    fn is_frozen(id: &T::TokenId) -> bool {
+        <Tokens<T>>::get(id).map_or(false, |details| details.is_frozen)
     }
 
     fn last_token() -> T::TokenId {
@@ -133,51 +704,532 @@ impl<T: Config> pallet::Pallet<T> {
         <LastTokenId<T>>::get().unwrap()
     }
 
-    fn token_inc() -> T::TokenId {
-        // unwrap safety: initialized at genesis_build
-        let token = Self::last_token();
-        <LastTokenId<T>>::put(token.saturating_add(1u32.into()));
+    /// First id from `LastTokenId` on that isn't in use, `LastTokenId` itself is left untouched.
+    /// Ids taken explicitly through `fungibles::Create` or `nonfungibles::Mutate` are skipped. Taking the
+    /// id moves `LastTokenId` past them, so every explicit id is skipped once at most
+    fn free_token_id() -> Result<T::TokenId, DispatchError> {
+        let mut id = Self::last_token();
+        while <Issuance<T>>::contains_key(id) {
+            id = id.checked_add(&1u32.into()).ok_or(Error::<T>::NoAvailableTokenId)?;
+        }
+
+        Ok(id)
+    }
+
+    /// Move the sequential ids past `id`, picked by `free_token_id`
+    fn take_token_id(id: T::TokenId) -> DispatchResult {
+        let next = id.checked_add(&1u32.into()).ok_or(Error::<T>::NoAvailableTokenId)?;
+        <LastTokenId<T>>::put(next);
 
-        return token;
+        Ok(())
+    }
+
+    /// Lowercase hex encoding of `id` zero padded to 64 characters, as required by EIP-1155.
+    /// Ids wider than 128 bits saturate
+    fn hex_id(id: &T::TokenId) -> [u8; 64] {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        let id: u128 = (*id).unique_saturated_into();
+        let mut res = [b'0'; 64];
+        for (i, byte) in id.to_be_bytes().iter().enumerate() {
+            res[32 + i * 2] = HEX[(byte >> 4) as usize];
+            res[33 + i * 2] = HEX[(byte & 0xf) as usize];
+        }
+
+        res
+    }
+
+    fn ensure_batch_size(len: usize) -> DispatchResult {
+        ensure!(len <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
+
+        Ok(())
+    }
+
+    /// Number of distinct ids of a batch, the storage of every id is only accessed once
+    fn distinct_ids(id_values: &[(T::TokenId, T::Balance)]) -> u32 {
+        id_values.iter().map(|(id, _)| *id).collect::<BTreeSet<_>>().len() as u32
+    }
+
+    /// Sum up the values of duplicate ids so every id is validated and written once
+    fn aggregate(
+        ids: &[T::TokenId],
+        values: &[T::Balance]
+    ) -> Result<BTreeMap<T::TokenId, T::Balance>, DispatchError> {
+        Self::ensure_batch_size(ids.len())?;
+
+        let mut res = BTreeMap::new();
+        for (id, value) in ids.iter().zip(values.iter()) {
+            let total = res.entry(*id).or_insert(T::Balance::zero());
+            *total = total.checked_add(value).ok_or(Error::<T>::Overflow)?;
+        }
+
+        Ok(res)
+    }
+
+    /// Ensure `account` holds at least the aggregated amount of every id
+    fn ensure_can_debit(
+        account: &T::AccountId,
+        amounts: &BTreeMap<T::TokenId, T::Balance>,
+        reasons: WithdrawReasons
+    ) -> DispatchResult {
+        for (id, amount) in amounts.iter() {
+            let new_balance = <Self as ERC1155<T::AccountId>>::balance_of(account, id)
+                .checked_sub(amount)
+                .ok_or(Error::<T>::OutOfFunds)?;
+            Self::ensure_can_withdraw(account, id, reasons, new_balance)?;
+        }
+
+        Ok(())
+    }
+
+    /// Part of the free balance of `who` frozen by locks matching any of `reasons`
+    pub fn frozen_balance(who: &T::AccountId, id: &T::TokenId, reasons: WithdrawReasons) -> T::Balance {
+        <Locks<T>>::get(who, id)
+            .iter()
+            .filter(|lock| lock.reasons.intersects(reasons))
+            .fold(T::Balance::zero(), |frozen, lock| frozen.max(lock.amount))
+    }
+
+    /// Ensure the free balance of `who` may drop to `new_balance` for `reasons`
+    pub fn ensure_can_withdraw(
+        who: &T::AccountId,
+        id: &T::TokenId,
+        reasons: WithdrawReasons,
+        new_balance: T::Balance
+    ) -> DispatchResult {
+        ensure!(
+            new_balance >= Self::frozen_balance(who, id, reasons),
+            Error::<T>::LiquidityRestrictions
+        );
+
+        Ok(())
+    }
+
+    /// Replace the locks of `who` on `id`
+    fn update_locks(who: &T::AccountId, id: &T::TokenId, locks: Vec<BalanceLock<T::Balance>>) {
+        if locks.is_empty() {
+            <Locks<T>>::remove(who, id);
+        } else {
+            <Locks<T>>::insert(who, id, WeakBoundedVec::force_from(locks, Some("Erc1155 Update Locks")));
+        }
+    }
+
+    fn free_of(who: &T::AccountId, id: &T::TokenId) -> T::Balance {
+        <Balances<T>>::get(who, id).unwrap_or(T::Balance::zero())
+    }
+
+    fn reserved_of(who: &T::AccountId, id: &T::TokenId) -> T::Balance {
+        <ReservedBalances<T>>::get(who, id).unwrap_or(T::Balance::zero())
+    }
+
+    fn total_of(who: &T::AccountId, id: &T::TokenId) -> T::Balance {
+        Self::free_of(who, id).saturating_add(Self::reserved_of(who, id))
+    }
+
+    /// Balances of `id` below this amount are removed as dust
+    pub fn min_balance(id: &T::TokenId) -> T::Balance {
+        <Tokens<T>>::get(id).map_or(T::Balance::zero(), |details| details.min_balance)
+    }
+
+    /// Ensure `who` ends up with at least the minimum balance of `id` after receiving `amount`
+    fn ensure_can_receive(who: &T::AccountId, id: &T::TokenId, amount: T::Balance) -> DispatchResult {
+        let total = Self::total_of(who, id).checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+        ensure!(total >= Self::min_balance(id), Error::<T>::BelowMinimum);
+
+        Ok(())
+    }
+
+    /// Ensure `who` still holds at least the minimum balance of `id` after losing `amount`,
+    /// unless it is allowed to die
+    fn ensure_keep_alive(
+        who: &T::AccountId,
+        id: &T::TokenId,
+        amount: T::Balance,
+        existence: ExistenceRequirement
+    ) -> DispatchResult {
+        if existence == ExistenceRequirement::KeepAlive {
+            let left = Self::total_of(who, id).saturating_sub(amount);
+            ensure!(!left.is_zero() && left >= Self::min_balance(id), Error::<T>::KeepAlive);
+        }
+
+        Ok(())
+    }
+
+    /// Remove the balances of `who` if they fell below the minimum balance of `id`,
+    /// the dust is handed to `DustRemoval`. Fixed supply tokens keep their dust, their issuance can't shrink.
+    /// Locked balances are kept as well until the locks are removed
+    fn reap(who: &T::AccountId, id: &T::TokenId) {
+        let total = Self::total_of(who, id);
+        if total.is_zero() || total >= Self::min_balance(id) || Self::is_fixed(id)
+            || !Self::frozen_balance(who, id, WithdrawReasons::all()).is_zero()
+        {
+            return;
+        }
+
+        Self::write_balance(who, id, T::Balance::zero());
+        Self::write_reserved(who, id, T::Balance::zero());
+        <NamedReserves<T>>::remove(who, id);
+        <Locks<T>>::remove(who, id);
+        Self::decrease_issuance(id, total);
+
+        T::DustRemoval::on_unbalanced(imbalance::NegativeMultiTokenImbalance::new(*id, total));
+        Self::deposit_event(Event::DustLost(who.clone(), *id, total));
+    }
+
+    /// Write the free balance of `who`, every write to Balances goes through here
+    fn write_balance(who: &T::AccountId, id: &T::TokenId, free: T::Balance) {
+        if free.is_zero() {
+            <Balances<T>>::remove(who, id);
+        } else {
+            <Balances<T>>::insert(who, id, free);
+        }
+        Self::sync_owner(who, id);
+    }
+
+    /// Write the reserved balance of `who`, every write to ReservedBalances goes through here
+    fn write_reserved(who: &T::AccountId, id: &T::TokenId, reserved: T::Balance) {
+        if reserved.is_zero() {
+            <ReservedBalances<T>>::remove(who, id);
+        } else {
+            <ReservedBalances<T>>::insert(who, id, reserved);
+        }
+        Self::sync_owner(who, id);
+    }
+
+    /// Keep track of the holder of a unique token after the balances of `who` changed
+    fn sync_owner(who: &T::AccountId, id: &T::TokenId) {
+        if !<UniqueTokens<T>>::get(id) {
+            return;
+        }
+
+        if !Self::free_of(who, id).saturating_add(Self::reserved_of(who, id)).is_zero() {
+            <Owners<T>>::insert(id, who);
+        } else if <Owners<T>>::get(id).as_ref() == Some(who) {
+            <Owners<T>>::remove(id);
+        }
+    }
+
+    fn is_fixed(id: &T::TokenId) -> bool {
+        <Tokens<T>>::get(id).map_or(false, |details| details.fixed_supply)
+    }
+
+    /// How much more of `id` may be issued before reaching its cap, the pending issuance counts as issued
+    pub fn issuable(id: &T::TokenId) -> T::Balance {
+        let details = <Tokens<T>>::get(id);
+        if details.as_ref().map_or(false, |details| details.fixed_supply) {
+            return T::Balance::zero();
+        }
+
+        let cap = details
+            .map(|details| details.max_supply)
+            .flatten()
+            .unwrap_or(T::Balance::max_value());
+        cap.saturating_sub(<Issuance<T>>::get(id).unwrap_or(T::Balance::zero()))
+            .saturating_sub(<PendingIssuance<T>>::get(id))
+    }
+
+    /// Ensure `amount` more of `id` can be issued
+    fn ensure_can_issue(id: &T::TokenId, amount: T::Balance) -> DispatchResult {
+        ensure!(!Self::is_fixed(id), Error::<T>::FixedSupply);
+        <Issuance<T>>::get(id)
+            .unwrap_or(T::Balance::zero())
+            .checked_add(&<PendingIssuance<T>>::get(id))
+            .and_then(|issued| issued.checked_add(&amount))
+            .ok_or(Error::<T>::Overflow)?;
+        ensure!(amount <= Self::issuable(id), Error::<T>::SupplyCapExceeded);
+
+        Ok(())
+    }
+
+    /// Record `amount` of `id` credited ahead of the issuance by a positive imbalance,
+    /// the supply cap is checked by the caller
+    fn add_pending_issuance(id: &T::TokenId, amount: T::Balance) {
+        if amount.is_zero() {
+            return;
+        }
+
+        match <PendingIssuance<T>>::get(id).checked_add(&amount) {
+            Some(pending) => <PendingIssuance<T>>::insert(id, pending),
+            // `ensure_can_issue` keeps the issuance and the pending issuance below the maximum balance
+            None => debug_assert!(false, "pending issuance of {:?} overflows", id),
+        }
+    }
+
+    /// Move up to `amount` of the pending issuance of `id` to the issuance, once a positive imbalance is dropped
+    fn settle_pending_issuance(id: &T::TokenId, amount: T::Balance) {
+        let pending = <PendingIssuance<T>>::get(id);
+        let settled = amount.min(pending);
+        if settled.is_zero() {
+            return;
+        }
+
+        let issuance = <Issuance<T>>::get(id).unwrap_or(T::Balance::zero());
+        match issuance.checked_add(&settled) {
+            Some(issuance) => {
+                <Issuance<T>>::insert(id, issuance);
+                Self::write_pending_issuance(id, pending - settled);
+            },
+            // `ensure_can_issue` keeps the issuance and the pending issuance below the maximum balance
+            None => debug_assert!(false, "issuance of {:?} overflows", id),
+        }
+    }
+
+    /// Lower the issuance of `id` by `amount` taken out of balances.
+    /// Whatever the issuance doesn't cover yet was credited by positive imbalances still pending,
+    /// it is taken out of the pending issuance so they never raise the issuance for it
+    fn decrease_issuance(id: &T::TokenId, amount: T::Balance) {
+        let issuance = <Issuance<T>>::get(id).unwrap_or(T::Balance::zero());
+        if let Some(issuance) = issuance.checked_sub(&amount) {
+            <Issuance<T>>::insert(id, issuance);
+            return;
+        }
+
+        match <PendingIssuance<T>>::get(id).checked_sub(&(amount - issuance)) {
+            Some(pending) => {
+                <Issuance<T>>::insert(id, T::Balance::zero());
+                Self::write_pending_issuance(id, pending);
+            },
+            // balances of every account sum up to the issuance and the pending issuance
+            None => debug_assert!(false, "issuance of {:?} underflows", id),
+        }
+    }
+
+    fn write_pending_issuance(id: &T::TokenId, pending: T::Balance) {
+        if pending.is_zero() {
+            <PendingIssuance<T>>::remove(id);
+        } else {
+            <PendingIssuance<T>>::insert(id, pending);
+        }
+    }
+
+    /// Ensure the sum of free and reserved balances of every account equals the issuance, for every token.
+    /// Walks the whole balance storage, meant for tests and debugging only
+    #[cfg(feature = "std")]
+    pub fn ensure_issuance_invariant() -> Result<(), &'static str> {
+        let mut totals = BTreeMap::<T::TokenId, T::Balance>::new();
+        for (_, id, balance) in <Balances<T>>::iter().chain(<ReservedBalances<T>>::iter()) {
+            let total = totals.entry(id).or_insert(T::Balance::zero());
+            *total = total.checked_add(&balance).ok_or("sum of balances overflows")?;
+        }
+
+        for (id, issuance) in <Issuance<T>>::iter() {
+            if totals.remove(&id).unwrap_or(T::Balance::zero()) != issuance {
+                return Err("sum of balances differs from the issuance");
+            }
+        }
+        if totals.values().any(|total| !total.is_zero()) {
+            return Err("balance of a token without issuance");
+        }
+
+        Ok(())
+    }
+
+    /// Create the unique token `id` owned by `owner`, the sequential ids are left untouched
+    fn do_create_unique(id: T::TokenId, creator: T::AccountId, owner: &T::AccountId) -> DispatchResult {
+        ensure!(!<Issuance<T>>::contains_key(id), Error::<T>::TokenAlreadyExists);
+
+        <Tokens<T>>::insert(id, TokenDetails::new(creator.clone(), Some(1u32.into()), false, 1u32.into()));
+        <UniqueTokens<T>>::insert(id, true);
+        Self::write_balance(owner, &id, 1u32.into());
+        <Issuance<T>>::insert(id, T::Balance::from(1u32));
+
+        Self::deposit_event(Event::Minted(id, owner.clone(), 1u32.into()));
+        Self::deposit_event(Event::TransferSingle(creator, None, Some(owner.clone()), id, 1u32.into()));
+        Ok(())
+    }
+
+    /// Decrease the free balance of `who` respecting locks for `reasons`
+    fn debit(
+        who: &T::AccountId,
+        id: &T::TokenId,
+        amount: T::Balance,
+        reasons: WithdrawReasons
+    ) -> DispatchResult {
+        let new_balance = <Balances<T>>::get(who, id)
+            .map(|b| b.checked_sub(&amount))
+            .flatten()
+            .ok_or(Error::<T>::OutOfFunds)?;
+        Self::ensure_can_withdraw(who, id, reasons, new_balance)?;
+        Self::write_balance(who, id, new_balance);
+
+        Ok(())
+    }
+
+    /// Move up to `amount` of the reserved balance of `from` to the free or reserved balance of `to`.
+    /// Returns the amount actually moved
+    fn move_reserved(
+        from: &T::AccountId,
+        to: &T::AccountId,
+        id: &T::TokenId,
+        amount: T::Balance,
+        status: BalanceStatus
+    ) -> Result<T::Balance, DispatchError> {
+        let reserved = Self::reserved_of(from, id);
+        let actual = amount.min(reserved);
+        if actual.is_zero() || (from == to && status == BalanceStatus::Reserved) {
+            return Ok(actual);
+        }
+        if from != to {
+            ensure!(!Self::is_frozen(id), Error::<T>::Frozen);
+            Self::ensure_can_receive(to, id, actual)?;
+        }
+
+        let credited = match status {
+            BalanceStatus::Free => Self::free_of(to, id),
+            BalanceStatus::Reserved => Self::reserved_of(to, id),
+        }
+        .checked_add(&actual)
+        .ok_or(Error::<T>::Overflow)?;
+        Self::write_reserved(from, id, reserved - actual);
+        match status {
+            BalanceStatus::Free => Self::write_balance(to, id, credited),
+            BalanceStatus::Reserved => Self::write_reserved(to, id, credited),
+        }
+        Self::reap(from, id);
+
+        Ok(actual)
+    }
+
+    /// Move `value` of `id` between accounts without emitting events.
+    /// `from` is reaped if left below the minimum balance, unless it must be kept alive
+    fn do_transfer(
+        from: &T::AccountId,
+        to: &T::AccountId,
+        id: &T::TokenId,
+        value: T::Balance,
+        existence: ExistenceRequirement
+    ) -> DispatchResult {
+        if value.is_zero() || from == to {
+            return Ok(());
+        }
+        ensure!(!Self::is_frozen(id), Error::<T>::Frozen);
+
+        let credited = Self::free_of(to, id).checked_add(&value).ok_or(Error::<T>::Overflow)?;
+        Self::ensure_can_receive(to, id, value)?;
+        Self::ensure_keep_alive(from, id, value, existence)?;
+        Self::debit(from, id, value, WithdrawReasons::TRANSFER)?;
+        Self::write_balance(to, id, credited);
+        Self::reap(from, id);
+
+        Ok(())
+    }
+
+    /// Credit `amount` of `id` to `account` and increase the issuance.
+    /// Only emits `Minted`, the transfer event is up to the caller
+    fn do_mint(
+        account: &T::AccountId,
+        id: &T::TokenId,
+        amount: T::Balance
+    ) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(())
+        }
+
+        let issuance = <Issuance<T>>::get(id)
+            .unwrap_or(T::Balance::zero())
+            .checked_add(&amount)
+            .ok_or(Error::<T>::Overflow)?;
+        let credited = Self::free_of(account, id).checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+        Self::ensure_can_receive(account, id, amount)?;
+        Self::write_balance(account, id, credited);
+        <Issuance<T>>::insert(id, issuance);
+
+        Self::deposit_event(Event::Minted(*id, account.clone(), amount));
+        Ok(())
+    }
+
+    /// Debit `amount` of `id` from `account` and decrease the issuance.
+    /// Only emits `Burned`, the transfer event is up to the caller
+    fn do_burn(
+        account: &T::AccountId,
+        id: &T::TokenId,
+        amount: T::Balance
+    ) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(())
+        }
+
+        ensure!(!Self::is_fixed(id), Error::<T>::FixedSupply);
+        ensure!(!Self::is_frozen(id), Error::<T>::Frozen);
+
+        Self::debit(account, id, amount, WithdrawReasons::all())?;
+        Self::reap(account, id);
+        // read after reaping since the dust of `account` may have left the issuance already
+        Self::decrease_issuance(id, amount);
+        // a burnt unique token leaves nothing behind for the next instance minted with its id
+        if <UniqueTokens<T>>::get(id) && <Issuance<T>>::get(id).map_or(true, |issuance| issuance.is_zero()) {
+            let _ = <Attributes<T>>::remove_prefix(id, None);
+        }
+
+        Self::deposit_event(Event::Burned(*id, account.clone(), amount));
+        Ok(())
     }
 }
 
 impl<T: Config> ERC1155<T::AccountId> for pallet::Pallet<T> {
     type TokenId = T::TokenId;
     type Balance = T::Balance;
-    type PositiveImbalance = imbalance::PositiveImbalance<T>;
-    type NegativeImbalance = imbalance::NegativeImbalance<T>;
 
+    #[transactional]
     fn safe_transfer_from(
+        operator: &T::AccountId,
         from: &T::AccountId,
         to: &T::AccountId,
         id: &T::TokenId,
         value: T::Balance,
-        _calldata: Option<Vec<u8>>
+        calldata: Option<Vec<u8>>
     ) -> DispatchResult {
         ensure!(
             *to != T::AccountId::default(),
             Error::<T>::AccountNotFound
         );
+        ensure!(
+            operator == from || Self::is_approved_for_all(from, operator),
+            Error::<T>::NotApproved
+        );
 
         if value.is_zero() || from == to {
             return Ok(());
         }
 
-        <Balances<T>>::try_mutate(from, *id, |balance| -> Result<(), Error<T>> {
-            *balance = Some(balance.map(|b| b.checked_sub(&value))
-                .flatten()
-                .ok_or(Error::<T>::OutOfFunds)?);
-            <Balances<T>>::mutate(to, *id, |balance_target| {
-                // Should we consider checked add?
-                *balance_target = Some(balance.unwrap().saturating_add(value));
-            });
+        Self::do_transfer(from, to, id, value, ExistenceRequirement::AllowDeath)?;
 
-            Ok(())
-        })?;
+        T::Receiver::on_erc1155_received(operator, Some(from), to, id, value, calldata.as_deref())?;
+        Self::deposit_event(Event::TransferSingle(operator.clone(), Some(from.clone()), Some(to.clone()), *id, value));
 
-        Self::deposit_event(Event::TransferSingle(Some(from.clone()), Some(to.clone()), *id, value));
-        // TODO: Handle ERC1155Receiver
+        Ok(())
+    }
+
+    #[transactional]
+    fn safe_batch_transfer_from(
+        operator: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        id_values: impl Iterator<Item = impl Borrow<(T::TokenId, T::Balance)>>,
+        calldata: Option<Vec<u8>>
+    ) -> DispatchResult {
+        ensure!(
+            *to != T::AccountId::default(),
+            Error::<T>::AccountNotFound
+        );
+        ensure!(
+            operator == from || Self::is_approved_for_all(from, operator),
+            Error::<T>::NotApproved
+        );
+
+        let (ids, values): (Vec<_>, Vec<_>) = id_values.map(|v| *v.borrow()).unzip();
+        let totals = Self::aggregate(&ids, &values)?;
+
+        // validate the whole batch first so a failing entry never leaves earlier ones applied
+        if from != to {
+            Self::ensure_can_debit(from, &totals, WithdrawReasons::TRANSFER)?;
+        }
+        for (id, value) in totals.iter() {
+            Self::do_transfer(from, to, id, *value, ExistenceRequirement::AllowDeath)?;
+        }
+
+        T::Receiver::on_erc1155_batch_received(operator, Some(from), to, &ids, &values, calldata.as_deref())?;
+        Self::deposit_event(Event::TransferBatch(operator.clone(), Some(from.clone()), Some(to.clone()), ids, values));
 
         Ok(())
     }
@@ -186,56 +1238,220 @@ impl<T: Config> ERC1155<T::AccountId> for pallet::Pallet<T> {
         <Balances<T>>::get(owner, id).clone().unwrap_or(T::Balance::zero())
     }
 
-    fn set_approval_for_all(_owner: &T::AccountId, _approved: bool) {
-        unimplemented!();
+    fn balance_of_batch(
+        owner_ids: impl Iterator<Item = impl Borrow<(T::AccountId, T::TokenId)>>,
+    ) -> Result<Vec<T::Balance>, DispatchError> {
+        let owner_ids = owner_ids.take(T::MaxBatchSize::get() as usize + 1).collect::<Vec<_>>();
+        Self::ensure_batch_size(owner_ids.len())?;
+
+        Ok(owner_ids.iter().map(|v| {
+            let (owner, id) = v.borrow();
+            <Self as ERC1155<T::AccountId>>::balance_of(owner, id)
+        })
+        .collect())
     }
 
-    fn is_approved_for_all(_owner: &T::AccountId, _operator: &T::AccountId) -> bool {
-        unimplemented!();
+    fn set_approval_for_all(owner: &T::AccountId, operator: &T::AccountId, approved: bool) -> DispatchResult {
+        ensure!(owner != operator, Error::<T>::SelfApproval);
+
+        if approved {
+            <OperatorApprovals<T>>::insert(owner, operator, true);
+        } else {
+            <OperatorApprovals<T>>::remove(owner, operator);
+        }
+
+        Self::deposit_event(Event::ApprovalForAll(owner.clone(), operator.clone(), approved));
+        Ok(())
+    }
+
+    fn is_approved_for_all(owner: &T::AccountId, operator: &T::AccountId) -> bool {
+        <OperatorApprovals<T>>::get(owner, operator)
     }
 }
 
 impl<T: Config> ERC1155Mintable<T::AccountId> for pallet::Pallet<T> {
+    type PositiveImbalance = imbalance::PositiveMultiTokenImbalance<T>;
+
+    #[transactional]
     fn mint(
+        operator: &T::AccountId,
         account: &T::AccountId,
         id: &Self::TokenId,
         amount: Self::Balance,
-        _calldata: Option<Vec<u8>>
-    ) -> Result<Self::PositiveImbalance, DispatchError> {
+        calldata: Option<Vec<u8>>
+    ) -> DispatchResult {
         ensure!(
             *account != T::AccountId::default(),
             Error::<T>::AccountNotFound
         );
 
         if amount.is_zero() {
-            return Ok(Self::PositiveImbalance::new(0u32.into(), *id))
+            return Ok(())
+        }
+        Self::ensure_can_issue(id, amount)?;
+
+        Self::do_mint(account, id, amount)?;
+        T::Receiver::on_erc1155_received(operator, None, account, id, amount, calldata.as_deref())?;
+        Self::deposit_event(Event::TransferSingle(operator.clone(), None, Some(account.clone()), *id, amount));
+
+        Ok(())
+    }
+
+    #[transactional]
+    fn mint_batch(
+        operator: &T::AccountId,
+        account: &T::AccountId,
+        id_amounts: impl Iterator<Item = impl Borrow<(T::TokenId, T::Balance)>>,
+        calldata: Option<Vec<u8>>
+    ) -> Result<Self::PositiveImbalance, DispatchError> {
+        ensure!(
+            *account != T::AccountId::default(),
+            Error::<T>::AccountNotFound
+        );
+
+        let (ids, amounts): (Vec<_>, Vec<_>) = id_amounts.map(|v| *v.borrow()).unzip();
+        let totals = Self::aggregate(&ids, &amounts)?;
+
+        for (id, amount) in totals.iter() {
+            Self::ensure_can_issue(id, *amount)?;
+        }
+        let mut res = imbalance::PositiveMultiTokenImbalance::<T>::zero();
+        for (id, amount) in totals.iter() {
+            Self::do_mint(account, id, *amount)?;
+            res.add(*id, *amount);
         }
 
-        let res = <Balances<T>>::mutate(account, id, |balance| {
-            // checked add?
-            *balance = Some(balance.unwrap_or(Self::Balance::zero()).saturating_add(amount));
-            Self::PositiveImbalance::new(amount, *id)
-        });
+        T::Receiver::on_erc1155_batch_received(operator, None, account, &ids, &amounts, calldata.as_deref())?;
+        Self::deposit_event(Event::TransferBatch(operator.clone(), None, Some(account.clone()), ids, amounts));
 
-        // TODO: ERC115Receiver
         Ok(res)
     }
 }
 
 impl<T: Config> ERC1155Burnable<T::AccountId> for pallet::Pallet<T> {
+    type NegativeImbalance = imbalance::NegativeMultiTokenImbalance<T>;
+
     fn burn(
         account: &T::AccountId,
         id: &Self::TokenId,
         amount: Self::Balance
+    ) -> DispatchResult {
+        Self::do_burn(account, id, amount)?;
+        if !amount.is_zero() {
+            Self::deposit_event(Event::TransferSingle(account.clone(), Some(account.clone()), None, *id, amount));
+        }
+
+        Ok(())
+    }
+
+    fn burn_batch(
+        account: &T::AccountId,
+        id_amounts: impl Iterator<Item = impl Borrow<(T::TokenId, T::Balance)>>
     ) -> Result<Self::NegativeImbalance, DispatchError> {
-        <Balances<T>>::try_mutate(account, id, |balance| {
-            *balance = Some(balance
-                .map(|b| b.checked_sub(&amount))
-                .flatten()
-                .ok_or(Error::<T>::OutOfFunds)?);
+        let (ids, amounts): (Vec<_>, Vec<_>) = id_amounts.map(|v| *v.borrow()).unzip();
+        let totals = Self::aggregate(&ids, &amounts)?;
 
-            Ok(Self::NegativeImbalance::new(amount, *id))
-        })
+        Self::ensure_can_debit(account, &totals, WithdrawReasons::all())?;
+        for (id, amount) in totals.iter() {
+            ensure!(amount.is_zero() || !Self::is_fixed(id), Error::<T>::FixedSupply);
+            ensure!(amount.is_zero() || !Self::is_frozen(id), Error::<T>::Frozen);
+        }
+        let mut res = imbalance::NegativeMultiTokenImbalance::<T>::zero();
+        for (id, amount) in totals.iter() {
+            Self::do_burn(account, id, *amount)?;
+            res.add(*id, *amount);
+        }
+
+        Self::deposit_event(Event::TransferBatch(account.clone(), Some(account.clone()), None, ids, amounts));
 
+        Ok(res)
+    }
+}
+
+impl<T: Config> ERC1155MetadataURI<T::AccountId> for pallet::Pallet<T> {
+    type TokenInfo = Vec<u8>;
+
+    fn uri(id: &T::TokenId) -> Vec<u8> {
+        let raw = <TokenUris<T>>::get(id)
+            .or_else(|| <BaseUri<T>>::get())
+            .map(Into::into)
+            .unwrap_or_default();
+
+        let hex = Self::hex_id(id);
+        let mut res = Vec::with_capacity(raw.len());
+        let mut rest = &raw[..];
+        while !rest.is_empty() {
+            if rest.starts_with(b"{id}") {
+                res.extend_from_slice(&hex);
+                rest = &rest[4..];
+            } else {
+                res.push(rest[0]);
+                rest = &rest[1..];
+            }
+        }
+
+        res
+    }
+}
+
+impl<T: Config> ERC1155MetadataURIExt<T::AccountId> for pallet::Pallet<T> {
+    fn set_uri(id: Option<&T::TokenId>, uri: &Vec<u8>) -> DispatchResult {
+        let bounded: T::TokenInfo = uri.clone()
+            .try_into()
+            .map_err(|_| Error::<T>::BadMetadata)?;
+
+        match id {
+            Some(id) => {
+                if uri.is_empty() {
+                    // the token falls back to the base URI, report that one
+                    <TokenUris<T>>::remove(id);
+                    Self::deposit_event(Event::URI(<Self as ERC1155MetadataURI<T::AccountId>>::uri(id), *id));
+                } else {
+                    <TokenUris<T>>::insert(id, bounded);
+                    Self::deposit_event(Event::URI(uri.clone(), *id));
+                }
+            },
+            None => {
+                if uri.is_empty() {
+                    <BaseUri<T>>::kill();
+                } else {
+                    <BaseUri<T>>::put(bounded);
+                }
+                Self::deposit_event(Event::BaseURI(uri.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Config> ERC1155Reservable<T::AccountId> for pallet::Pallet<T> {
+    fn lock(owner: &T::AccountId, id: &T::TokenId, amount: T::Balance) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(());
+        }
+
+        let reserved = Self::reserved_of(owner, id).checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+        Self::debit(owner, id, amount, WithdrawReasons::RESERVE)?;
+        Self::write_reserved(owner, id, reserved);
+
+        Self::deposit_event(Event::Reserved(owner.clone(), *id, amount));
+        Ok(())
+    }
+
+    fn unlock(owner: &T::AccountId, id: &T::TokenId, amount: T::Balance) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(());
+        }
+
+        let left = Self::reserved_of(owner, id)
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::InsufficientReserve)?;
+        let free = Self::free_of(owner, id).checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+        Self::write_reserved(owner, id, left);
+        Self::write_balance(owner, id, free);
+
+        Self::deposit_event(Event::Unreserved(owner.clone(), *id, amount));
+        Ok(())
     }
 }
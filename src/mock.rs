@@ -0,0 +1,147 @@
+use crate as pallet_erc1155;
+use crate::{erc1155::ERC1155Receiver, token::Erc1155Token};
+use frame_support::{
+    dispatch::{DispatchError, DispatchResult}, ensure, parameter_types, traits::{Everything, GenesisBuild},
+    weights::Weight, BoundedVec,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Erc1155: pallet_erc1155::{Pallet, Call, Storage, Event<T>, Config<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+}
+
+parameter_types! {
+    pub const StringLimit: u32 = 64;
+    pub const MaxReserves: u32 = 2;
+    pub const MaxLocks: u32 = 10;
+    pub const MaxBatchSize: u32 = 4;
+}
+
+/// Account refusing every token sent to it
+pub const REJECTING: u64 = 9;
+
+/// Rejects every transfer to `REJECTING`
+pub struct RejectingReceiver;
+
+impl ERC1155Receiver<u64, u128, u64> for RejectingReceiver {
+    fn on_erc1155_received(
+        _operator: &u64,
+        _from: Option<&u64>,
+        to: &u64,
+        _id: &u128,
+        _value: u64,
+        _calldata: Option<&[u8]>
+    ) -> DispatchResult {
+        ensure!(*to != REJECTING, DispatchError::Other("rejected"));
+        Ok(())
+    }
+
+    fn on_erc1155_batch_received(
+        _operator: &u64,
+        _from: Option<&u64>,
+        to: &u64,
+        _ids: &[u128],
+        _values: &[u64],
+        _calldata: Option<&[u8]>
+    ) -> DispatchResult {
+        ensure!(*to != REJECTING, DispatchError::Other("rejected"));
+        Ok(())
+    }
+
+    fn on_erc1155_received_weight() -> Weight {
+        1_000
+    }
+
+    fn on_erc1155_batch_received_weight(n: u32) -> Weight {
+        1_000 * n as Weight
+    }
+}
+
+impl pallet_erc1155::Config for Test {
+    type Balance = u64;
+    type TokenId = u128;
+    type Event = Event;
+    type CreateOrigin = EnsureSigned<u64>;
+    type ForceOrigin = EnsureRoot<u64>;
+    type StringLimit = StringLimit;
+    type TokenInfo = BoundedVec<u8, StringLimit>;
+    type ReserveIdentifier = [u8; 8];
+    type MaxReserves = MaxReserves;
+    type MaxLocks = MaxLocks;
+    type MaxBatchSize = MaxBatchSize;
+    type Receiver = RejectingReceiver;
+    type DustRemoval = ();
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const TokenA: u128 = 0;
+    pub const TokenB: u128 = 1;
+}
+
+/// `Currency` adapter over the token created at genesis
+pub type Token = Erc1155Token<Test, TokenA>;
+
+/// `Currency` adapter over the first token created after genesis
+pub type Created = Erc1155Token<Test, TokenB>;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    new_test_ext_with(pallet_erc1155::GenesisConfig::<Test> {
+        initial_token: TokenA::get(),
+        ..Default::default()
+    })
+}
+
+pub fn new_test_ext_with(config: pallet_erc1155::GenesisConfig<Test>) -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    config.assimilate_storage(&mut t).unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,285 @@
+//! Random call sequences checking that balances and issuance never drift apart
+
+use crate::{mock::*, erc1155::ERC1155, Balances, Issuance, PendingIssuance, ReservedBalances};
+use frame_support::traits::{
+    BalanceStatus, Currency, ExistenceRequirement, Get, Imbalance, ReservableCurrency, SameOrOther, WithdrawReasons,
+};
+use proptest::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Accounts taking part, account 0 is rejected as a recipient by the pallet
+const ACCOUNTS: u64 = 4;
+
+#[derive(Clone, Debug)]
+enum Op {
+    CreateToken { creator: u64, owner: u64, supply: u64, max_supply: Option<u64>, fixed: bool, min_balance: u64 },
+    Mint { issuer: u64, to: u64, token: u8, amount: u64 },
+    Burn { who: u64, token: u8, amount: u64 },
+    Transfer { from: u64, to: u64, token: u8, amount: u64 },
+    BatchTransfer { from: u64, to: u64, values: Vec<(u8, u64)> },
+    Deposit { who: u64, amount: u64 },
+    Withdraw { who: u64, amount: u64, keep_alive: bool },
+    CurrencyTransfer { from: u64, to: u64, amount: u64 },
+    Slash { who: u64, amount: u64 },
+    Reserve { who: u64, amount: u64 },
+    Unreserve { who: u64, amount: u64 },
+    Repatriate { from: u64, to: u64, amount: u64, free: bool },
+    SetBalance { who: u64, amount: u64 },
+    HoldDeposit { who: u64, amount: u64 },
+    HoldWithdraw { who: u64, amount: u64, keep_alive: bool },
+    HoldSlash { who: u64, amount: u64 },
+    HoldIssue { amount: u64 },
+    HoldBurn { amount: u64 },
+    SplitHeld { positive: bool, amount: u64 },
+    MergeHeld { positive: bool },
+    OffsetHeld,
+    ReleaseHeld { positive: bool, index: u8 },
+}
+
+/// Imbalances of `Token` kept alive across operations
+#[derive(Default)]
+struct Held {
+    positive: Vec<<Token as Currency<u64>>::PositiveImbalance>,
+    negative: Vec<<Token as Currency<u64>>::NegativeImbalance>,
+}
+
+fn account() -> impl Strategy<Value = u64> {
+    0..=ACCOUNTS
+}
+
+/// Mostly small amounts, with values close to the limits to hit the overflow checks
+fn amount() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        8 => 0..1_000u64,
+        1 => (u64::max_value() - 1_000)..=u64::max_value(),
+        1 => any::<u64>(),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (account(), account(), amount(), proptest::option::of(amount()), any::<bool>(), 0..10u64)
+            .prop_map(|(creator, owner, supply, max_supply, fixed, min_balance)| {
+                Op::CreateToken { creator, owner, supply, max_supply, fixed, min_balance }
+            }),
+        (account(), account(), any::<u8>(), amount())
+            .prop_map(|(issuer, to, token, amount)| Op::Mint { issuer, to, token, amount }),
+        (account(), any::<u8>(), amount())
+            .prop_map(|(who, token, amount)| Op::Burn { who, token, amount }),
+        (account(), account(), any::<u8>(), amount())
+            .prop_map(|(from, to, token, amount)| Op::Transfer { from, to, token, amount }),
+        (account(), account(), proptest::collection::vec((any::<u8>(), amount()), 0..5))
+            .prop_map(|(from, to, values)| Op::BatchTransfer { from, to, values }),
+        (account(), amount()).prop_map(|(who, amount)| Op::Deposit { who, amount }),
+        (account(), amount(), any::<bool>())
+            .prop_map(|(who, amount, keep_alive)| Op::Withdraw { who, amount, keep_alive }),
+        (account(), account(), amount())
+            .prop_map(|(from, to, amount)| Op::CurrencyTransfer { from, to, amount }),
+        (account(), amount()).prop_map(|(who, amount)| Op::Slash { who, amount }),
+        (account(), amount()).prop_map(|(who, amount)| Op::Reserve { who, amount }),
+        (account(), amount()).prop_map(|(who, amount)| Op::Unreserve { who, amount }),
+        (account(), account(), amount(), any::<bool>())
+            .prop_map(|(from, to, amount, free)| Op::Repatriate { from, to, amount, free }),
+        (account(), amount()).prop_map(|(who, amount)| Op::SetBalance { who, amount }),
+    ]
+}
+
+/// Operations on `Token` whose imbalances are held, merged and settled in any order
+fn held_op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (account(), amount()).prop_map(|(who, amount)| Op::HoldDeposit { who, amount }),
+        (account(), amount(), any::<bool>())
+            .prop_map(|(who, amount, keep_alive)| Op::HoldWithdraw { who, amount, keep_alive }),
+        (account(), amount()).prop_map(|(who, amount)| Op::HoldSlash { who, amount }),
+        amount().prop_map(|amount| Op::HoldIssue { amount }),
+        amount().prop_map(|amount| Op::HoldBurn { amount }),
+        (any::<bool>(), amount()).prop_map(|(positive, amount)| Op::SplitHeld { positive, amount }),
+        any::<bool>().prop_map(|positive| Op::MergeHeld { positive }),
+        Just(Op::OffsetHeld),
+        (any::<bool>(), any::<u8>()).prop_map(|(positive, index)| Op::ReleaseHeld { positive, index }),
+    ]
+}
+
+/// Existing token picked by `index`, token 0 is created at genesis
+fn token(index: u8) -> u128 {
+    index as u128 % Erc1155::last_token().max(1)
+}
+
+fn existence(keep_alive: bool) -> ExistenceRequirement {
+    if keep_alive { ExistenceRequirement::KeepAlive } else { ExistenceRequirement::AllowDeath }
+}
+
+/// Split the last imbalance of `held` in two
+fn split_last<I: Imbalance<u64>>(held: &mut Vec<I>, amount: u64) {
+    if let Some(imbalance) = held.pop() {
+        let (first, second) = imbalance.split(amount);
+        held.push(first);
+        held.push(second);
+    }
+}
+
+/// Merge the last two imbalances of `held`, nothing may be lost to saturation
+fn merge_last<I: Imbalance<u64>>(held: &mut Vec<I>) {
+    if held.len() < 2 {
+        return;
+    }
+    let (first, second) = (held.pop().unwrap(), held.pop().unwrap());
+    let total = first.peek() as u128 + second.peek() as u128;
+    let merged = first.merge(second);
+    assert_eq!(merged.peek() as u128, total, "merging imbalances saturates");
+    held.push(merged);
+}
+
+/// Drop the imbalance of `held` picked by `index`
+fn release<I>(held: &mut Vec<I>, index: u8) {
+    if !held.is_empty() {
+        let index = index as usize % held.len();
+        drop(held.remove(index));
+    }
+}
+
+/// Apply `op`, failing calls are expected and ignored. Imbalances are dropped right away,
+/// except for the `Hold` operations keeping them in `held`
+fn apply(op: Op, held: &mut Held) {
+    match op {
+        Op::CreateToken { creator, owner, supply, max_supply, fixed, min_balance } => {
+            let _ = Erc1155::create_token(Origin::signed(creator), owner, supply, max_supply, fixed, min_balance);
+        },
+        Op::Mint { issuer, to, token: index, amount } => {
+            let _ = Erc1155::mint(Origin::signed(issuer), to, token(index), amount);
+        },
+        Op::Burn { who, token: index, amount } => {
+            let _ = Erc1155::burn(Origin::signed(who), token(index), amount);
+        },
+        Op::Transfer { from, to, token: index, amount } => {
+            let _ = <Erc1155 as ERC1155<u64>>::safe_transfer_from(&from, &from, &to, &token(index), amount, None);
+        },
+        Op::BatchTransfer { from, to, values } => {
+            let values = values.into_iter().map(|(index, amount)| (token(index), amount));
+            let _ = <Erc1155 as ERC1155<u64>>::safe_batch_transfer_from(&from, &from, &to, values, None);
+        },
+        Op::Deposit { who, amount } => {
+            let _ = Token::deposit_creating(&who, amount);
+        },
+        Op::Withdraw { who, amount, keep_alive } => {
+            let _ = Token::withdraw(&who, amount, WithdrawReasons::all(), existence(keep_alive));
+        },
+        Op::CurrencyTransfer { from, to, amount } => {
+            let _ = Token::transfer(&from, &to, amount, ExistenceRequirement::AllowDeath);
+        },
+        Op::Slash { who, amount } => {
+            let _ = Token::slash(&who, amount);
+        },
+        Op::Reserve { who, amount } => {
+            let _ = Token::reserve(&who, amount);
+        },
+        Op::Unreserve { who, amount } => {
+            let _ = Token::unreserve(&who, amount);
+        },
+        Op::Repatriate { from, to, amount, free } => {
+            let status = if free { BalanceStatus::Free } else { BalanceStatus::Reserved };
+            let _ = Token::repatriate_reserved(&from, &to, amount, status);
+        },
+        Op::SetBalance { who, amount } => {
+            let _ = Token::make_free_balance_be(&who, amount);
+        },
+        Op::HoldDeposit { who, amount } => {
+            held.positive.push(Token::deposit_creating(&who, amount));
+        },
+        Op::HoldWithdraw { who, amount, keep_alive } => {
+            if let Ok(imbalance) = Token::withdraw(&who, amount, WithdrawReasons::all(), existence(keep_alive)) {
+                held.negative.push(imbalance);
+            }
+        },
+        Op::HoldSlash { who, amount } => {
+            held.negative.push(Token::slash(&who, amount).0);
+        },
+        Op::HoldIssue { amount } => {
+            held.negative.push(Token::issue(amount));
+        },
+        Op::HoldBurn { amount } => {
+            held.positive.push(Token::burn(amount));
+        },
+        Op::SplitHeld { positive, amount } => {
+            if positive { split_last(&mut held.positive, amount) } else { split_last(&mut held.negative, amount) }
+        },
+        Op::MergeHeld { positive } => {
+            if positive { merge_last(&mut held.positive) } else { merge_last(&mut held.negative) }
+        },
+        Op::OffsetHeld => {
+            if !held.positive.is_empty() && !held.negative.is_empty() {
+                let (positive, negative) = (held.positive.pop().unwrap(), held.negative.pop().unwrap());
+                match positive.offset(negative) {
+                    SameOrOther::Same(positive) => held.positive.push(positive),
+                    SameOrOther::Other(negative) => held.negative.push(negative),
+                    SameOrOther::None => {},
+                }
+            }
+        },
+        Op::ReleaseHeld { positive, index } => {
+            if positive { release(&mut held.positive, index) } else { release(&mut held.negative, index) }
+        },
+    }
+}
+
+/// Check that the balances of every token add up to its issuance, counting the imbalances held on `Token`.
+/// Sums are taken in `u128` so that saturated arithmetic in the pallet can't go unnoticed
+fn check_conservation(held: &Held) -> Result<(), String> {
+    let mut totals = BTreeMap::<u128, u128>::new();
+    for (_, id, balance) in <Balances<Test>>::iter().chain(<ReservedBalances<Test>>::iter()) {
+        *totals.entry(id).or_default() += balance as u128;
+    }
+    let held_positive = held.positive.iter().map(|imbalance| imbalance.peek() as u128).sum::<u128>();
+    let held_negative = held.negative.iter().map(|imbalance| imbalance.peek() as u128).sum::<u128>();
+
+    let ids = <Issuance<Test>>::iter_keys()
+        .chain(<PendingIssuance<Test>>::iter_keys())
+        .chain(totals.keys().copied())
+        .collect::<BTreeSet<_>>();
+    for id in ids {
+        let issuance = <Issuance<Test>>::get(id).unwrap_or(0) as u128;
+        let pending = <PendingIssuance<Test>>::get(id) as u128;
+        let (held_positive, held_negative) = if id == TokenA::get() { (held_positive, held_negative) } else { (0, 0) };
+        let total = totals.get(&id).copied().unwrap_or(0);
+
+        if issuance + pending > u64::max_value() as u128 {
+            return Err(format!("issuance {} and pending issuance {} of {} overflow", issuance, pending, id));
+        }
+        if pending != held_positive {
+            return Err(format!("pending issuance {} of {} differs from the held imbalances {}", pending, id, held_positive));
+        }
+        if total + held_negative != issuance + pending {
+            return Err(format!(
+                "balances {} and held imbalances {} of {} differ from the issuance {} and pending issuance {}",
+                total, held_negative, id, issuance, pending
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn balances_sum_up_to_issuance(ops in proptest::collection::vec(prop_oneof![3 => op(), 1 => held_op()], 1..64)) {
+        new_test_ext().execute_with(|| {
+            let mut held = Held::default();
+            for op in ops {
+                let description = format!("{:?}", op);
+                apply(op, &mut held);
+                if let Err(e) = check_conservation(&held) {
+                    panic!("{} after {}", e, description);
+                }
+            }
+
+            // once every imbalance is settled the issuance is final
+            drop(held);
+            assert_eq!(<PendingIssuance<Test>>::iter().count(), 0);
+            if let Err(e) = Erc1155::ensure_issuance_invariant() {
+                panic!("{} after settling the held imbalances", e);
+            }
+        });
+    }
+}
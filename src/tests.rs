@@ -0,0 +1,1257 @@
+use crate::{
+    mock::*, Balances, Error,
+    erc1155::{ERC1155, ERC1155Burnable, ERC1155MetadataURI, ERC1155Mintable, ERC1155Receiver},
+    imbalance::{PositiveImbalance, PositiveMultiTokenImbalance},
+    weights::WeightInfo,
+};
+use frame_support::{
+    assert_noop, assert_ok,
+    weights::GetDispatchInfo,
+    traits::{
+        BalanceStatus, Currency, ExistenceRequirement::{AllowDeath, KeepAlive}, Imbalance, LockableCurrency,
+        NamedReservableCurrency, ReservableCurrency, SameOrOther, SignedImbalance, WithdrawReasons,
+        tokens::{fungibles, nonfungibles, WithdrawConsequence},
+    },
+};
+
+const ID_1: [u8; 8] = *b"id_1____";
+const ID_2: [u8; 8] = *b"id_2____";
+const ID_3: [u8; 8] = *b"id_3____";
+
+fn fund(who: u64, amount: u64) {
+    let _ = Token::deposit_creating(&who, amount);
+}
+
+/// Create a token through the extrinsic, account 1 holds every role
+fn create(owner: u64, supply: u64) -> u128 {
+    let id = Erc1155::free_token_id().unwrap();
+    assert_ok!(Erc1155::create_token(Origin::signed(1), owner, supply, None, false, 0));
+    id
+}
+
+#[test]
+fn deposit_creating_increases_issuance() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        fund(2, 50);
+
+        assert_eq!(Token::free_balance(&1), 100);
+        assert_eq!(Token::total_balance(&2), 50);
+        assert_eq!(Token::total_issuance(), 150);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn deposit_into_existing_requires_an_account() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(Token::deposit_into_existing(&1, 10), Error::<Test>::AccountNotFound);
+
+        fund(1, 10);
+        let imbalance = Token::deposit_into_existing(&1, 5).unwrap();
+        assert_eq!(imbalance.peek(), 5);
+        drop(imbalance);
+
+        assert_eq!(Token::free_balance(&1), 15);
+        assert_eq!(Token::total_issuance(), 15);
+    });
+}
+
+#[test]
+fn transfer_moves_free_balance() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+
+        assert_ok!(Token::transfer(&1, &2, 60, AllowDeath));
+        assert_eq!(Token::free_balance(&1), 40);
+        assert_eq!(Token::free_balance(&2), 60);
+        assert_eq!(Token::total_issuance(), 100);
+
+        assert_noop!(Token::transfer(&1, &2, 41, AllowDeath), Error::<Test>::OutOfFunds);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn transfer_keeps_the_recipient_balance() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        fund(2, 5);
+
+        assert_ok!(Token::transfer(&1, &2, 30, AllowDeath));
+        assert_eq!(Token::free_balance(&1), 70);
+        assert_eq!(Token::free_balance(&2), 35);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn can_slash_compares_against_free_balance() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+
+        assert!(Token::can_slash(&1, 0));
+        assert!(Token::can_slash(&1, 100));
+        assert!(!Token::can_slash(&1, 101));
+        assert!(!Token::can_slash(&2, 1));
+    });
+}
+
+#[test]
+fn slash_is_clamped_to_the_balance() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+
+        let (imbalance, remaining) = Token::slash(&1, 150);
+        assert_eq!(imbalance.peek(), 100);
+        assert_eq!(remaining, 50);
+        assert_eq!(Token::free_balance(&1), 0);
+
+        drop(imbalance);
+        assert_eq!(Token::total_issuance(), 0);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn slash_takes_reserved_balance_after_free_balance() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        assert_ok!(Token::reserve(&1, 60));
+
+        let (imbalance, remaining) = Token::slash(&1, 70);
+        assert_eq!(imbalance.peek(), 70);
+        assert_eq!(remaining, 0);
+        assert_eq!(Token::free_balance(&1), 0);
+        assert_eq!(Token::reserved_balance(&1), 30);
+
+        drop(imbalance);
+        assert_eq!(Token::total_issuance(), 30);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn slash_reserved_is_clamped_to_the_reserve() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        assert_ok!(Token::reserve(&1, 50));
+
+        let (imbalance, remaining) = Token::slash_reserved(&1, 80);
+        assert_eq!(imbalance.peek(), 50);
+        assert_eq!(remaining, 30);
+        assert_eq!(Token::free_balance(&1), 50);
+        assert_eq!(Token::reserved_balance(&1), 0);
+
+        drop(imbalance);
+        assert_eq!(Token::total_issuance(), 50);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn burn_is_clamped_to_the_issuance() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+
+        let imbalance = Token::burn(150);
+        assert_eq!(imbalance.peek(), 100);
+        assert_eq!(Token::total_issuance(), 0);
+
+        // the positive imbalance squares the issuance back up
+        drop(imbalance);
+        assert_eq!(Token::total_issuance(), 100);
+    });
+}
+
+#[test]
+fn issue_is_clamped_on_overflow() {
+    new_test_ext().execute_with(|| {
+        fund(1, u64::max_value() - 10);
+
+        let imbalance = Token::issue(100);
+        assert_eq!(imbalance.peek(), 10);
+        assert_eq!(Token::total_issuance(), u64::max_value());
+
+        drop(imbalance);
+        assert_eq!(Token::total_issuance(), u64::max_value() - 10);
+    });
+}
+
+#[test]
+fn issue_is_clamped_to_the_supply_cap() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Erc1155::do_create_token(1, 1, 10, Some(50), false, 0), Ok(TokenB::get()));
+
+        let imbalance = Created::issue(100);
+        assert_eq!(imbalance.peek(), 40);
+        assert_eq!(Created::total_issuance(), 50);
+    });
+}
+
+#[test]
+fn make_free_balance_be_reports_the_change() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+
+        match Token::make_free_balance_be(&1, 150) {
+            SignedImbalance::Positive(imbalance) => assert_eq!(imbalance.peek(), 50),
+            SignedImbalance::Negative(_) => panic!("balance increased"),
+        }
+        assert_eq!(Token::total_issuance(), 150);
+
+        match Token::make_free_balance_be(&1, 20) {
+            SignedImbalance::Negative(imbalance) => assert_eq!(imbalance.peek(), 130),
+            SignedImbalance::Positive(_) => panic!("balance decreased"),
+        }
+        assert_eq!(Token::free_balance(&1), 20);
+        assert_eq!(Token::total_issuance(), 20);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn withdraw_decreases_issuance() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+
+        let imbalance = Token::withdraw(&1, 30, WithdrawReasons::TRANSFER, AllowDeath).unwrap();
+        assert_eq!(imbalance.peek(), 30);
+        drop(imbalance);
+
+        assert_eq!(Token::free_balance(&1), 70);
+        assert_eq!(Token::total_issuance(), 70);
+        assert_noop!(
+            Token::withdraw(&1, 71, WithdrawReasons::TRANSFER, AllowDeath),
+            Error::<Test>::OutOfFunds
+        );
+    });
+}
+
+#[test]
+fn reserve_and_unreserve_move_between_balances() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+
+        assert!(Token::can_reserve(&1, 100));
+        assert!(!Token::can_reserve(&1, 101));
+        assert_noop!(Token::reserve(&1, 101), Error::<Test>::OutOfFunds);
+
+        assert_ok!(Token::reserve(&1, 40));
+        assert_eq!(Token::free_balance(&1), 60);
+        assert_eq!(Token::reserved_balance(&1), 40);
+        assert_eq!(Token::total_balance(&1), 100);
+
+        assert_eq!(Token::unreserve(&1, 50), 10);
+        assert_eq!(Token::free_balance(&1), 100);
+        assert_eq!(Token::reserved_balance(&1), 0);
+        assert_eq!(Token::total_issuance(), 100);
+    });
+}
+
+#[test]
+fn repatriate_reserved_moves_to_free_or_reserved() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        fund(2, 1);
+        assert_ok!(Token::reserve(&1, 50));
+
+        assert_eq!(Token::repatriate_reserved(&1, &2, 20, BalanceStatus::Reserved), Ok(0));
+        assert_eq!(Token::reserved_balance(&2), 20);
+
+        assert_eq!(Token::repatriate_reserved(&1, &2, 40, BalanceStatus::Free), Ok(10));
+        assert_eq!(Token::free_balance(&2), 31);
+        assert_eq!(Token::reserved_balance(&1), 0);
+        assert_eq!(Token::total_balance(&1), 50);
+        assert_eq!(Token::total_issuance(), 101);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn repatriate_reserved_to_self_unreserves() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        assert_ok!(Token::reserve(&1, 50));
+
+        assert_eq!(Token::repatriate_reserved(&1, &1, 60, BalanceStatus::Free), Ok(10));
+        assert_eq!(Token::free_balance(&1), 100);
+        assert_eq!(Token::reserved_balance(&1), 0);
+    });
+}
+
+#[test]
+fn named_reserves_are_tracked_per_identifier() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+
+        assert_ok!(Token::reserve_named(&ID_1, &1, 30));
+        assert_ok!(Token::reserve_named(&ID_2, &1, 20));
+        assert_noop!(Token::reserve_named(&ID_3, &1, 10), Error::<Test>::TooManyReserves);
+
+        assert_eq!(Token::reserved_balance_named(&ID_1, &1), 30);
+        assert_eq!(Token::reserved_balance_named(&ID_2, &1), 20);
+        assert_eq!(Token::reserved_balance(&1), 50);
+
+        assert_eq!(Token::unreserve_named(&ID_1, &1, 40), 10);
+        assert_eq!(Token::reserved_balance_named(&ID_1, &1), 0);
+        assert_eq!(Token::free_balance(&1), 80);
+
+        let (imbalance, remaining) = Token::slash_reserved_named(&ID_2, &1, 5);
+        assert_eq!(imbalance.peek(), 5);
+        assert_eq!(remaining, 0);
+        assert_eq!(Token::reserved_balance_named(&ID_2, &1), 15);
+        assert_eq!(Token::reserved_balance(&1), 15);
+    });
+}
+
+#[test]
+fn repatriate_reserved_named_keeps_the_identifier() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        fund(2, 1);
+        assert_ok!(Token::reserve_named(&ID_1, &1, 30));
+
+        assert_eq!(Token::repatriate_reserved_named(&ID_1, &1, &2, 10, BalanceStatus::Reserved), Ok(0));
+        assert_eq!(Token::reserved_balance_named(&ID_1, &1), 20);
+        assert_eq!(Token::reserved_balance_named(&ID_1, &2), 10);
+        assert_eq!(Token::reserved_balance(&2), 10);
+
+        assert_eq!(Token::repatriate_reserved_named(&ID_1, &1, &2, 25, BalanceStatus::Free), Ok(5));
+        assert_eq!(Token::reserved_balance_named(&ID_1, &1), 0);
+        assert_eq!(Token::free_balance(&2), 21);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn lock_restricts_withdrawals() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        Token::set_lock(ID_1, &1, 60, WithdrawReasons::all());
+
+        assert_noop!(Token::transfer(&1, &2, 41, AllowDeath), Error::<Test>::LiquidityRestrictions);
+        assert!(Token::can_reserve(&1, 40));
+        assert!(!Token::can_reserve(&1, 41));
+        assert_noop!(Token::reserve(&1, 41), Error::<Test>::LiquidityRestrictions);
+        assert_ok!(Token::transfer(&1, &2, 40, AllowDeath));
+
+        Token::remove_lock(ID_1, &1);
+        assert_ok!(Token::transfer(&1, &2, 60, AllowDeath));
+    });
+}
+
+#[test]
+fn lock_applies_to_its_reasons_only() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        Token::set_lock(ID_1, &1, 100, WithdrawReasons::RESERVE);
+
+        assert!(!Token::can_reserve(&1, 1));
+        assert_noop!(Token::reserve(&1, 1), Error::<Test>::LiquidityRestrictions);
+        assert_ok!(Token::transfer(&1, &2, 50, AllowDeath));
+    });
+}
+
+#[test]
+fn locks_combine_by_maximum() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        Token::set_lock(ID_1, &1, 30, WithdrawReasons::all());
+        Token::set_lock(ID_2, &1, 50, WithdrawReasons::all());
+
+        assert_noop!(Token::transfer(&1, &2, 51, AllowDeath), Error::<Test>::LiquidityRestrictions);
+
+        // replacing a lock shrinks it
+        Token::set_lock(ID_2, &1, 10, WithdrawReasons::all());
+        assert_ok!(Token::transfer(&1, &2, 70, AllowDeath));
+    });
+}
+
+#[test]
+fn extend_lock_never_shrinks() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        Token::set_lock(ID_1, &1, 50, WithdrawReasons::all());
+        Token::extend_lock(ID_1, &1, 30, WithdrawReasons::all());
+
+        assert_noop!(Token::transfer(&1, &2, 51, AllowDeath), Error::<Test>::LiquidityRestrictions);
+
+        Token::extend_lock(ID_1, &1, 80, WithdrawReasons::all());
+        assert_noop!(Token::transfer(&1, &2, 21, AllowDeath), Error::<Test>::LiquidityRestrictions);
+        assert_ok!(Token::transfer(&1, &2, 20, AllowDeath));
+    });
+}
+
+#[test]
+fn zero_balances_are_removed() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+        assert_ok!(Token::transfer(&1, &2, 100, AllowDeath));
+
+        assert!(!Balances::<Test>::contains_key(1, TokenA::get()));
+        assert_eq!(Balances::<Test>::get(2, TokenA::get()), Some(100));
+    });
+}
+
+#[test]
+fn balance_below_minimum_is_reaped() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Erc1155::do_create_token(1, 1, 100, None, false, 10));
+        assert_eq!(Created::minimum_balance(), 10);
+
+        assert_ok!(Created::transfer(&1, &2, 95, AllowDeath));
+        assert_eq!(Created::free_balance(&1), 0);
+        assert!(!Balances::<Test>::contains_key(1, TokenB::get()));
+        System::assert_has_event(Event::Erc1155(crate::Event::DustLost(1, TokenB::get(), 5)));
+
+        assert_eq!(Created::total_issuance(), 95);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn reducible_balance_can_be_withdrawn_keeping_alive() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+
+        let reducible = <Erc1155 as fungibles::Inspect<u64>>::reducible_balance(TokenA::get(), &1, true);
+        assert_eq!(reducible, 99);
+        assert_eq!(<Erc1155 as fungibles::Transfer<u64>>::transfer(TokenA::get(), &1, &2, reducible, true), Ok(99));
+        assert_eq!(Token::free_balance(&1), 1);
+        assert_eq!(<Erc1155 as fungibles::Inspect<u64>>::reducible_balance(TokenA::get(), &1, true), 0);
+
+        // the held balance keeps the account alive on its own
+        assert_ok!(Token::reserve(&2, 10));
+        let reducible = <Erc1155 as fungibles::Inspect<u64>>::reducible_balance(TokenA::get(), &2, true);
+        assert_eq!(reducible, 89);
+        assert_ok!(Token::withdraw(&2, reducible, WithdrawReasons::all(), KeepAlive));
+    });
+}
+
+#[test]
+fn locked_balance_is_never_reaped() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Erc1155::do_create_token(1, 1, 22, None, false, 10));
+        assert_ok!(Created::transfer(&1, &2, 10, AllowDeath));
+        Created::set_lock(ID_1, &1, 5, WithdrawReasons::all());
+
+        // the locked 5 are left below the minimum but kept
+        assert_ok!(Created::transfer(&1, &2, 7, AllowDeath));
+        assert_eq!(Created::free_balance(&1), 5);
+        assert_eq!(Created::total_issuance(), 22);
+        assert_noop!(
+            Created::withdraw(&1, 1, WithdrawReasons::TRANSFER, AllowDeath),
+            Error::<Test>::LiquidityRestrictions
+        );
+
+        // once unlocked the dust is reaped
+        Created::remove_lock(ID_1, &1);
+        let imbalance = Created::withdraw(&1, 1, WithdrawReasons::TRANSFER, AllowDeath).unwrap();
+        drop(imbalance);
+        assert_eq!(Created::free_balance(&1), 0);
+        System::assert_has_event(Event::Erc1155(crate::Event::DustLost(1, TokenB::get(), 4)));
+        assert_eq!(Created::total_issuance(), 17);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn keep_alive_prevents_reaping() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Erc1155::do_create_token(1, 1, 100, None, false, 10));
+
+        assert_noop!(Created::transfer(&1, &2, 95, KeepAlive), Error::<Test>::KeepAlive);
+        assert_noop!(Created::transfer(&1, &2, 100, KeepAlive), Error::<Test>::KeepAlive);
+        assert_noop!(
+            Created::withdraw(&1, 95, WithdrawReasons::TRANSFER, KeepAlive),
+            Error::<Test>::KeepAlive
+        );
+        assert_ok!(Created::transfer(&1, &2, 90, KeepAlive));
+        assert_eq!(Created::free_balance(&1), 10);
+    });
+}
+
+#[test]
+fn receiving_below_minimum_fails() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Erc1155::do_create_token(1, 1, 100, None, false, 10));
+
+        assert_noop!(Created::transfer(&1, &2, 5, AllowDeath), Error::<Test>::BelowMinimum);
+
+        let imbalance = Created::deposit_creating(&2, 5);
+        assert_eq!(imbalance.peek(), 0);
+        drop(imbalance);
+        assert_eq!(Created::free_balance(&2), 0);
+        assert_eq!(Created::total_issuance(), 100);
+    });
+}
+
+#[test]
+fn creating_a_token_below_minimum_fails() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Erc1155::do_create_token(1, 1, 5, None, false, 10),
+            Error::<Test>::BelowMinimum
+        );
+    });
+}
+
+#[test]
+fn create_unique_token_skips_ids_in_use() {
+    new_test_ext().execute_with(|| {
+        // the genesis token holds the first id
+        assert_ok!(Erc1155::create_unique_token(Origin::signed(1), 2));
+        assert_eq!(Erc1155::owner_of(TokenA::get() + 1), Some(2));
+        assert_eq!(Erc1155::last_token(), TokenA::get() + 2);
+
+        assert_ok!(Erc1155::create_unique_token(Origin::signed(1), 3));
+        assert_eq!(Erc1155::owner_of(TokenA::get() + 2), Some(3));
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn explicit_ids_leave_the_sequential_ids_alone() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(<Erc1155 as fungibles::Create<u64>>::create(u128::max_value(), 2, false, 0));
+        assert_eq!(Erc1155::last_token(), TokenA::get());
+        assert_eq!(Erc1155::do_create_token(1, 1, 10, None, false, 0), Ok(TokenB::get()));
+
+        // ids taken explicitly are skipped by the sequential ids
+        assert_ok!(<Erc1155 as fungibles::Create<u64>>::create(TokenB::get() + 1, 2, false, 0));
+        assert_eq!(Erc1155::do_create_token(1, 1, 10, None, false, 0), Ok(TokenB::get() + 2));
+        assert_eq!(Erc1155::token_details(TokenB::get() + 1).unwrap().admin, 2);
+        assert_noop!(
+            <Erc1155 as fungibles::Create<u64>>::create(TokenB::get(), 2, false, 0),
+            Error::<Test>::TokenAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn minted_instances_leave_the_sequential_ids_alone() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(<Erc1155 as nonfungibles::Mutate<u64>>::mint_into(&(), &u128::max_value(), &2));
+        assert_eq!(Erc1155::owner_of(u128::max_value()), Some(2));
+        assert_eq!(Erc1155::last_token(), TokenA::get());
+
+        assert_ok!(<Erc1155 as nonfungibles::Mutate<u64>>::mint_into(&(), &TokenB::get(), &2));
+        assert_ok!(Erc1155::create_unique_token(Origin::signed(1), 3));
+        assert_eq!(Erc1155::owner_of(TokenB::get() + 1), Some(3));
+        assert_noop!(
+            <Erc1155 as nonfungibles::Mutate<u64>>::mint_into(&(), &TokenB::get(), &3),
+            Error::<Test>::TokenAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn sequential_ids_skip_every_explicit_id() {
+    new_test_ext().execute_with(|| {
+        for id in 1..=40 {
+            assert_ok!(<Erc1155 as fungibles::Create<u64>>::create(id, 2, false, 0));
+        }
+        assert_ok!(<Erc1155 as nonfungibles::Mutate<u64>>::mint_into(&(), &41, &2));
+
+        assert_eq!(Erc1155::do_create_token(1, 1, 10, None, false, 0), Ok(42));
+        assert_eq!(Erc1155::last_token(), 43);
+        assert_ok!(Erc1155::create_unique_token(Origin::signed(1), 3));
+        assert_eq!(Erc1155::owner_of(43), Some(3));
+    });
+}
+
+#[test]
+fn sequential_ids_fail_when_they_run_out() {
+    new_test_ext().execute_with(|| {
+        crate::LastTokenId::<Test>::put(u128::max_value() - 1);
+        assert_eq!(Erc1155::do_create_token(1, 1, 10, None, false, 0), Ok(u128::max_value() - 1));
+        assert_noop!(Erc1155::do_create_token(1, 1, 10, None, false, 0), Error::<Test>::NoAvailableTokenId);
+    });
+}
+
+#[test]
+fn create_token_skips_the_genesis_token() {
+    new_test_ext().execute_with(|| {
+        fund(1, 30);
+
+        assert_eq!(Erc1155::do_create_token(2, 2, 20, None, false, 100), Ok(TokenA::get() + 1));
+        assert_eq!(Erc1155::token_details(TokenA::get()), None);
+        assert_eq!(Token::minimum_balance(), 0);
+        assert_eq!(Token::free_balance(&1), 30);
+        assert_eq!(Token::total_issuance(), 30);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn make_free_balance_be_respects_the_issuance_limits() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Erc1155::do_create_token(1, 1, 10, Some(50), false, 0));
+
+        match Created::make_free_balance_be(&1, 100) {
+            SignedImbalance::Positive(imbalance) => assert_eq!(imbalance.peek(), 0),
+            SignedImbalance::Negative(_) => panic!("balance can't decrease"),
+        }
+        assert_eq!(Created::free_balance(&1), 10);
+
+        let _ = Created::make_free_balance_be(&1, 50);
+        assert_eq!(Created::total_issuance(), 50);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn make_free_balance_be_never_overflows_the_issuance() {
+    new_test_ext().execute_with(|| {
+        let _ = Token::make_free_balance_be(&1, u64::max_value());
+        let _ = Token::make_free_balance_be(&2, u64::max_value());
+
+        assert_eq!(Token::free_balance(&2), 0);
+        assert_eq!(Token::total_issuance(), u64::max_value());
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn create_token_credits_the_initial_supply() {
+    new_test_ext().execute_with(|| {
+        let id = create(2, 100);
+
+        assert_eq!(Erc1155::balance_of(2, id), Some(100));
+        assert_eq!(Erc1155::issuance(id), Some(100));
+        assert_eq!(Erc1155::token_details(id).unwrap().admin, 1);
+        System::assert_has_event(Event::Erc1155(crate::Event::Minted(id, 2, 100)));
+        System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(1, None, Some(2), id, 100)));
+
+        assert_eq!(create(2, 1), id + 1);
+    });
+}
+
+#[test]
+fn create_token_respects_the_supply_cap() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Erc1155::create_token(Origin::signed(1), 1, 100, Some(50), false, 0),
+            Error::<Test>::SupplyCapExceeded
+        );
+        assert_noop!(
+            Erc1155::create_token(Origin::none(), 1, 100, None, false, 0),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn safe_transfer_credits_the_recipient_balance() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, 100);
+        assert_ok!(Erc1155::safe_transfer(Origin::signed(1), 2, id, 10));
+
+        // the recipient keeps its own balance, it is not overwritten by the sender's
+        assert_ok!(Erc1155::safe_transfer(Origin::signed(1), 2, id, 30));
+        assert_eq!(Erc1155::balance_of(1, id), Some(60));
+        assert_eq!(Erc1155::balance_of(2, id), Some(40));
+        System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(1, Some(1), Some(2), id, 30)));
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn safe_transfer_fails_without_funds() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, 100);
+
+        assert_noop!(Erc1155::safe_transfer(Origin::signed(1), 2, id, 101), Error::<Test>::OutOfFunds);
+        assert_noop!(Erc1155::safe_transfer(Origin::signed(2), 1, id, 1), Error::<Test>::OutOfFunds);
+        assert_noop!(Erc1155::safe_transfer(Origin::signed(1), 0, id, 1), Error::<Test>::AccountNotFound);
+    });
+}
+
+#[test]
+fn transfer_from_requires_an_approved_operator() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, 100);
+
+        assert_noop!(Erc1155::transfer_from(Origin::signed(3), 1, 2, id, 10), Error::<Test>::NotApproved);
+
+        assert_ok!(Erc1155::set_approval_for_all(Origin::signed(1), 3, true));
+        System::assert_last_event(Event::Erc1155(crate::Event::ApprovalForAll(1, 3, true)));
+        assert_ok!(Erc1155::transfer_from(Origin::signed(3), 1, 2, id, 10));
+        assert_eq!(Erc1155::balance_of(2, id), Some(10));
+        System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(3, Some(1), Some(2), id, 10)));
+
+        assert_ok!(Erc1155::set_approval_for_all(Origin::signed(1), 3, false));
+        assert_noop!(Erc1155::transfer_from(Origin::signed(3), 1, 2, id, 10), Error::<Test>::NotApproved);
+    });
+}
+
+#[test]
+fn owners_cannot_approve_themselves() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(Erc1155::set_approval_for_all(Origin::signed(1), 1, true), Error::<Test>::SelfApproval);
+        assert_noop!(Erc1155::set_approval_for_all(Origin::signed(1), 1, false), Error::<Test>::SelfApproval);
+        assert!(!Erc1155::operator_approvals(1, 1));
+    });
+}
+
+#[test]
+fn safe_batch_transfer_moves_every_token() {
+    new_test_ext().execute_with(|| {
+        let a = create(1, 100);
+        let b = create(1, 50);
+
+        assert_ok!(Erc1155::safe_batch_transfer(Origin::signed(1), 2, vec![(a, 10), (b, 20), (a, 5)]));
+        assert_eq!(Erc1155::balance_of(1, a), Some(85));
+        assert_eq!(Erc1155::balance_of(2, a), Some(15));
+        assert_eq!(Erc1155::balance_of(2, b), Some(20));
+        System::assert_last_event(Event::Erc1155(
+            crate::Event::TransferBatch(1, Some(1), Some(2), vec![a, b, a], vec![10, 20, 5])
+        ));
+    });
+}
+
+#[test]
+fn safe_batch_transfer_is_atomic() {
+    new_test_ext().execute_with(|| {
+        let a = create(1, 100);
+        let b = create(1, 50);
+
+        assert_noop!(
+            Erc1155::safe_batch_transfer(Origin::signed(1), 2, vec![(a, 10), (b, 51)]),
+            Error::<Test>::OutOfFunds
+        );
+        // duplicate ids are checked against their sum
+        assert_noop!(
+            Erc1155::safe_batch_transfer(Origin::signed(1), 2, vec![(b, 30), (b, 30)]),
+            Error::<Test>::OutOfFunds
+        );
+    });
+}
+
+#[test]
+fn receiver_rejection_reverts_the_transfer() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, 100);
+        let rejected = sp_runtime::DispatchError::Other("rejected");
+
+        assert_noop!(Erc1155::safe_transfer(Origin::signed(1), REJECTING, id, 10), rejected);
+        assert_noop!(Erc1155::safe_batch_transfer(Origin::signed(1), REJECTING, vec![(id, 10)]), rejected);
+        assert_noop!(Erc1155::mint(Origin::signed(1), REJECTING, id, 10), rejected);
+        assert_noop!(Erc1155::mint_batch(Origin::signed(1), REJECTING, vec![(id, 10)]), rejected);
+        assert_eq!(Erc1155::balance_of(1, id), Some(100));
+        assert_eq!(Erc1155::balance_of(REJECTING, id), None);
+        assert_eq!(Erc1155::issuance(id), Some(100));
+    });
+}
+
+#[test]
+fn receiver_weight_is_charged() {
+    new_test_ext().execute_with(|| {
+        let info = Call::Erc1155(crate::Call::safe_transfer(2, 0, 10)).get_dispatch_info();
+        assert_eq!(info.weight, <() as WeightInfo>::safe_transfer() + RejectingReceiver::on_erc1155_received_weight());
+
+        let info = Call::Erc1155(crate::Call::mint_batch(2, vec![(0, 1), (0, 1)])).get_dispatch_info();
+        assert_eq!(
+            info.weight,
+            <() as WeightInfo>::mint_batch(2, 2) + RejectingReceiver::on_erc1155_batch_received_weight(2)
+        );
+    });
+}
+
+#[test]
+fn mint_requires_the_issuer() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, 100);
+
+        assert_noop!(Erc1155::mint(Origin::signed(2), 2, id, 10), Error::<Test>::NoPermission);
+        assert_noop!(Erc1155::mint(Origin::signed(1), 2, id + 1, 10), Error::<Test>::TokenNotFound);
+
+        assert_ok!(Erc1155::mint(Origin::signed(1), 2, id, 10));
+        assert_eq!(Erc1155::balance_of(2, id), Some(10));
+        assert_eq!(Erc1155::issuance(id), Some(110));
+        System::assert_has_event(Event::Erc1155(crate::Event::Minted(id, 2, 10)));
+        System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(1, None, Some(2), id, 10)));
+
+        assert_ok!(Erc1155::set_team(Origin::signed(1), id, 3, 1, 1));
+        assert_ok!(Erc1155::mint(Origin::signed(3), 3, id, 5));
+        assert_eq!(Erc1155::issuance(id), Some(115));
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn mint_overflow_fails() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, u64::max_value());
+
+        assert_noop!(Erc1155::mint(Origin::signed(1), 2, id, 1), Error::<Test>::Overflow);
+    });
+}
+
+#[test]
+fn supply_cap_and_fixed_supply_are_enforced() {
+    new_test_ext().execute_with(|| {
+        let capped = Erc1155::free_token_id().unwrap();
+        assert_ok!(Erc1155::create_token(Origin::signed(1), 1, 10, Some(15), false, 0));
+        assert_noop!(Erc1155::mint(Origin::signed(1), 1, capped, 6), Error::<Test>::SupplyCapExceeded);
+        assert_ok!(Erc1155::mint(Origin::signed(1), 1, capped, 5));
+
+        let fixed = Erc1155::free_token_id().unwrap();
+        assert_ok!(Erc1155::create_token(Origin::signed(1), 1, 10, None, true, 0));
+        assert_noop!(Erc1155::mint(Origin::signed(1), 1, fixed, 1), Error::<Test>::FixedSupply);
+        assert_noop!(Erc1155::burn(Origin::signed(1), fixed, 1), Error::<Test>::FixedSupply);
+        assert_ok!(Erc1155::safe_transfer(Origin::signed(1), 2, fixed, 5));
+    });
+}
+
+#[test]
+fn currency_never_changes_a_fixed_supply() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Erc1155::create_token(Origin::signed(1), 1, 100, None, true, 10));
+        let id = TokenB::get();
+        assert_ok!(Created::reserve(&1, 20));
+
+        assert_eq!(Created::burn(50).peek(), 0);
+        assert!(!Created::can_slash(&1, 10));
+        let (slashed, left) = Created::slash(&1, 10);
+        assert_eq!((slashed.peek(), left), (0, 10));
+        let (slashed, left) = Created::slash_reserved(&1, 10);
+        assert_eq!((slashed.peek(), left), (0, 10));
+        assert_noop!(Created::withdraw(&1, 10, WithdrawReasons::all(), AllowDeath), Error::<Test>::FixedSupply);
+        match Created::make_free_balance_be(&1, 40) {
+            SignedImbalance::Positive(imbalance) => assert_eq!(imbalance.peek(), 0),
+            SignedImbalance::Negative(_) => panic!("the balance of a fixed supply token can't shrink"),
+        }
+        assert_eq!((Created::free_balance(&1), Created::reserved_balance(&1)), (80, 20));
+
+        // dust is kept rather than reaped
+        assert_ok!(Erc1155::safe_transfer(Origin::signed(1), 2, id, 15));
+        assert_ok!(Erc1155::safe_transfer(Origin::signed(2), 3, id, 10));
+        assert_eq!(Erc1155::balance_of(2, id), Some(5));
+
+        assert_eq!(Erc1155::issuance(id), Some(100));
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn burn_decreases_issuance() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, 100);
+
+        assert_noop!(Erc1155::burn(Origin::signed(1), id, 101), Error::<Test>::OutOfFunds);
+        assert_ok!(Erc1155::burn(Origin::signed(1), id, 40));
+        assert_eq!(Erc1155::balance_of(1, id), Some(60));
+        assert_eq!(Erc1155::issuance(id), Some(60));
+        System::assert_has_event(Event::Erc1155(crate::Event::Burned(id, 1, 40)));
+        System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(1, Some(1), None, id, 40)));
+    });
+}
+
+#[test]
+fn admin_burn_requires_the_admin() {
+    new_test_ext().execute_with(|| {
+        let id = create(2, 100);
+
+        assert_noop!(Erc1155::admin_burn(Origin::signed(2), 2, id, 10), Error::<Test>::NoPermission);
+        assert_ok!(Erc1155::admin_burn(Origin::signed(1), 2, id, 10));
+        assert_eq!(Erc1155::balance_of(2, id), Some(90));
+        assert_eq!(Erc1155::issuance(id), Some(90));
+    });
+}
+
+#[test]
+fn batch_mint_and_burn_settle_issuance() {
+    new_test_ext().execute_with(|| {
+        let a = create(1, 100);
+        let b = create(1, 50);
+
+        assert_ok!(Erc1155::mint_batch(Origin::signed(1), 2, vec![(a, 10), (b, 20)]));
+        assert_eq!(Erc1155::issuance(a), Some(110));
+        assert_eq!(Erc1155::issuance(b), Some(70));
+
+        assert_ok!(Erc1155::burn_batch(Origin::signed(2), vec![(a, 10), (b, 5)]));
+        assert_eq!(Erc1155::balance_of(2, b), Some(15));
+        assert_eq!(Erc1155::issuance(a), Some(100));
+        assert_eq!(Erc1155::issuance(b), Some(65));
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn frozen_tokens_cannot_move() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, 100);
+
+        assert_noop!(Erc1155::freeze(Origin::signed(2), id), Error::<Test>::NoPermission);
+        assert_ok!(Erc1155::freeze(Origin::signed(1), id));
+        System::assert_last_event(Event::Erc1155(crate::Event::Frozen(id)));
+        assert_noop!(Erc1155::safe_transfer(Origin::signed(1), 2, id, 10), Error::<Test>::Frozen);
+
+        assert_ok!(Erc1155::thaw(Origin::signed(1), id));
+        assert_ok!(Erc1155::safe_transfer(Origin::signed(1), 2, id, 10));
+    });
+}
+
+#[test]
+fn frozen_tokens_cannot_be_burnt() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, 100);
+        assert_ok!(Erc1155::freeze(Origin::signed(1), id));
+
+        assert_eq!(<Erc1155 as fungibles::Inspect<u64>>::can_withdraw(id, &1, 10), WithdrawConsequence::Frozen);
+        assert_noop!(Erc1155::burn(Origin::signed(1), id, 10), Error::<Test>::Frozen);
+        assert_noop!(Erc1155::burn_batch(Origin::signed(1), vec![(id, 10)]), Error::<Test>::Frozen);
+        assert_noop!(Erc1155::admin_burn(Origin::signed(1), 1, id, 10), Error::<Test>::Frozen);
+        assert_noop!(
+            <Erc1155 as fungibles::Mutate<u64>>::burn_from(id, &1, 10),
+            sp_runtime::TokenError::Frozen
+        );
+
+        assert_ok!(Erc1155::thaw(Origin::signed(1), id));
+        assert_ok!(Erc1155::burn(Origin::signed(1), id, 10));
+        assert_eq!(Erc1155::issuance(id), Some(90));
+    });
+}
+
+#[test]
+fn frozen_tokens_cannot_move_reserved_balance() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Erc1155::do_create_token(1, 1, 100, None, false, 0));
+        assert_ok!(Created::reserve(&1, 50));
+        assert_ok!(Erc1155::freeze(Origin::signed(1), TokenB::get()));
+
+        assert_noop!(Created::repatriate_reserved(&1, &2, 10, BalanceStatus::Free), Error::<Test>::Frozen);
+        assert_noop!(
+            <Erc1155 as fungibles::MutateHold<u64>>::transfer_held(TokenB::get(), &1, &2, 10, false, true),
+            Error::<Test>::Frozen
+        );
+        // the reserve of the holder itself can still be released
+        assert_eq!(Created::unreserve(&1, 10), 0);
+    });
+}
+
+#[test]
+fn roles_can_be_handed_over() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, 100);
+
+        assert_noop!(Erc1155::transfer_ownership(Origin::signed(2), id, 2), Error::<Test>::NoPermission);
+        assert_ok!(Erc1155::transfer_ownership(Origin::signed(1), id, 2));
+        System::assert_last_event(Event::Erc1155(crate::Event::OwnerChanged(id, 2)));
+        assert_noop!(Erc1155::set_team(Origin::signed(1), id, 1, 1, 1), Error::<Test>::NoPermission);
+
+        assert_ok!(Erc1155::set_team(Origin::signed(2), id, 3, 2, 4));
+        let details = Erc1155::token_details(id).unwrap();
+        assert_eq!((details.creator, details.issuer, details.admin, details.freezer), (1, 3, 2, 4));
+    });
+}
+
+#[test]
+fn uri_substitutes_the_hex_id() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Erc1155::set_uri(Origin::signed(1), None, b"https://token/{id}.json".to_vec()),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(Erc1155::set_uri(Origin::root(), None, b"https://token/{id}.json".to_vec()));
+
+        let mut expected = b"https://token/".to_vec();
+        expected.extend_from_slice(&[b'0'; 62]);
+        expected.extend_from_slice(b"2a.json");
+        assert_eq!(<Erc1155 as ERC1155MetadataURI<u64>>::uri(&42), expected);
+
+        assert_ok!(Erc1155::set_uri(Origin::root(), Some(42), b"ipfs://special".to_vec()));
+        assert_eq!(<Erc1155 as ERC1155MetadataURI<u64>>::uri(&42), b"ipfs://special".to_vec());
+
+        assert_noop!(
+            Erc1155::set_uri(Origin::root(), None, [b'a'; 65].to_vec()),
+            Error::<Test>::BadMetadata
+        );
+
+        // empty URIs remove the stored ones
+        assert_ok!(Erc1155::set_uri(Origin::root(), Some(42), Vec::new()));
+        assert_eq!(<Erc1155 as ERC1155MetadataURI<u64>>::uri(&42), expected);
+        System::assert_last_event(Event::Erc1155(crate::Event::URI(expected.clone(), 42)));
+        assert_ok!(Erc1155::set_uri(Origin::root(), None, Vec::new()));
+        assert!(!crate::BaseUri::<Test>::exists());
+        System::assert_last_event(Event::Erc1155(crate::Event::BaseURI(Vec::new())));
+    });
+}
+
+#[test]
+fn burning_a_unique_token_clears_its_attributes() {
+    new_test_ext().execute_with(|| {
+        let id = Erc1155::free_token_id().unwrap();
+        assert_ok!(Erc1155::create_unique_token(Origin::signed(1), 2));
+        assert_ok!(<Erc1155 as nonfungibles::Mutate<u64>>::set_attribute(&(), &id, b"color", b"red"));
+
+        assert_ok!(Erc1155::burn(Origin::signed(2), id, 1));
+        assert_eq!(<Erc1155 as nonfungibles::Inspect<u64>>::attribute(&(), &id, b"color"), None);
+
+        // an instance minted again with the id starts without attributes
+        assert_eq!(<Erc1155 as fungibles::Destroy<u64>>::destroy(id, 0, None), Ok(0));
+        assert_ok!(<Erc1155 as nonfungibles::Mutate<u64>>::mint_into(&(), &id, &3));
+        assert_eq!(<Erc1155 as nonfungibles::Inspect<u64>>::attribute(&(), &id, b"color"), None);
+    });
+}
+
+#[test]
+fn unique_tokens_track_their_owner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Erc1155::create_unique_token(Origin::signed(1), 2));
+        let id = Erc1155::last_token() - 1;
+        assert!(Erc1155::is_unique(id));
+        assert_eq!(Erc1155::owner_of(id), Some(2));
+        System::assert_last_event(Event::Erc1155(crate::Event::TransferSingle(1, None, Some(2), id, 1)));
+
+        assert_ok!(Erc1155::safe_transfer(Origin::signed(2), 3, id, 1));
+        assert_eq!(Erc1155::owner_of(id), Some(3));
+        assert_noop!(Erc1155::mint(Origin::signed(1), 3, id, 1), Error::<Test>::SupplyCapExceeded);
+
+        assert_ok!(Erc1155::burn(Origin::signed(3), id, 1));
+        assert_eq!(Erc1155::owner_of(id), None);
+    });
+}
+
+#[test]
+fn imbalances_settle_issuance_on_drop() {
+    new_test_ext().execute_with(|| {
+        let positive = Token::deposit_creating(&1, 100);
+        // balance is credited right away, issuance waits for the imbalance
+        assert_eq!(Token::free_balance(&1), 100);
+        assert_eq!(Token::total_issuance(), 0);
+
+        let (first, second) = positive.split(30);
+        assert_eq!((first.peek(), second.peek()), (30, 70));
+        drop(first);
+        assert_eq!(Token::total_issuance(), 30);
+
+        let negative = Token::withdraw(&1, 20, WithdrawReasons::TRANSFER, AllowDeath).unwrap();
+        match second.offset(negative) {
+            SameOrOther::Same(rest) => assert_eq!(rest.peek(), 50),
+            _ => panic!("positive imbalance is larger"),
+        }
+        assert_eq!(Token::total_issuance(), 80);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn held_imbalances_count_towards_the_supply_cap() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Erc1155::do_create_token(1, 1, 10, Some(50), false, 0));
+
+        let first = Created::deposit_into_existing(&1, 30).unwrap();
+        let second = Created::deposit_creating(&2, 10);
+        assert_eq!((first.peek(), second.peek()), (30, 10));
+        // the issuance waits for the imbalances, the cap doesn't
+        assert_eq!(Created::total_issuance(), 10);
+        assert_noop!(Created::deposit_into_existing(&1, 1), Error::<Test>::SupplyCapExceeded);
+        assert_eq!(Created::deposit_creating(&3, 1).peek(), 0);
+        assert_eq!(Created::issue(1).peek(), 0);
+        assert_noop!(Erc1155::mint(Origin::signed(1), 3, TokenB::get(), 1), Error::<Test>::SupplyCapExceeded);
+
+        drop(first.merge(second));
+        assert_eq!(Created::total_issuance(), 50);
+        assert_eq!(crate::PendingIssuance::<Test>::get(TokenB::get()), 0);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn imbalances_settle_in_any_order() {
+    new_test_ext().execute_with(|| {
+        let positive = Token::deposit_creating(&1, 100);
+        let negative = Token::withdraw(&1, 100, WithdrawReasons::TRANSFER, AllowDeath).unwrap();
+
+        // the negative imbalance is larger than the issuance, the rest comes out of the pending issuance
+        drop(negative);
+        assert_eq!(Token::total_issuance(), 0);
+        drop(positive);
+        assert_eq!(Token::total_issuance(), 0);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn zero_imbalances_are_usable() {
+    new_test_ext().execute_with(|| {
+        fund(1, 100);
+
+        let zero = PositiveImbalance::<Test, TokenA>::zero();
+        assert_eq!(zero.peek(), 0);
+        assert!(zero.drop_zero().is_ok());
+
+        let merged = PositiveImbalance::<Test, TokenA>::default().merge(Token::deposit_creating(&1, 5));
+        assert_eq!(merged.peek(), 5);
+        drop(merged);
+        assert_eq!(Token::total_issuance(), 105);
+    });
+}
+
+#[test]
+fn batch_mint_returns_the_imbalance() {
+    new_test_ext().execute_with(|| {
+        let a = create(1, 100);
+        let b = create(1, 50);
+
+        let imbalance = <Erc1155 as ERC1155Mintable<u64>>::mint_batch(
+            &1, &2, vec![(a, 10), (b, 20), (a, 1)].into_iter(), None
+        ).unwrap();
+        assert_eq!(imbalance.peek(&a), 11);
+        assert_eq!(imbalance.peek(&b), 20);
+        // the issuance is settled by the batch itself, not by the imbalance
+        assert_eq!(Erc1155::issuance(a), Some(111));
+        assert_eq!(Erc1155::issuance(b), Some(70));
+
+        let (first, rest) = imbalance.split(a, 5);
+        assert_eq!(first.peek(&a), 5);
+        assert_eq!(rest.peek(&a), 6);
+        drop(first);
+        assert_eq!(Erc1155::issuance(a), Some(111));
+
+        let burnt = <Erc1155 as ERC1155Burnable<u64>>::burn_batch(&2, vec![(b, 15)].into_iter()).unwrap();
+        assert_eq!(Erc1155::issuance(b), Some(55));
+        let (rest, burnt) = rest.offset(burnt);
+        assert_eq!(rest.peek(&b), 5);
+        assert!(burnt.is_zero());
+        drop((rest, burnt));
+
+        assert_eq!(Erc1155::issuance(a), Some(111));
+        assert_eq!(Erc1155::issuance(b), Some(55));
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+    });
+}
+
+#[test]
+fn merging_multi_token_records_fails_on_overflow() {
+    new_test_ext().execute_with(|| {
+        let mut record = PositiveMultiTokenImbalance::<Test>::new(1, u64::MAX - 1);
+        assert_ok!(record.subsume(PositiveMultiTokenImbalance::new(2, 5)));
+
+        assert_eq!(
+            record.subsume(PositiveMultiTokenImbalance::new(1, 2)),
+            Err(sp_runtime::ArithmeticError::Overflow)
+        );
+        // nothing was added
+        assert_eq!(record.peek(&1), u64::MAX - 1);
+        assert_eq!(record.peek(&2), 5);
+
+        let record = record.merge(PositiveMultiTokenImbalance::new(1, 1)).unwrap();
+        assert_eq!(record.peek(&1), u64::MAX);
+    });
+}
+
+#[test]
+fn batches_are_bounded() {
+    new_test_ext().execute_with(|| {
+        let id = create(1, 100);
+        let batch = vec![(id, 1); 5];
+
+        assert_noop!(Erc1155::safe_batch_transfer(Origin::signed(1), 2, batch.clone()), Error::<Test>::BatchTooLarge);
+        assert_noop!(Erc1155::mint_batch(Origin::signed(1), 2, batch.clone()), Error::<Test>::BatchTooLarge);
+        assert_noop!(Erc1155::burn_batch(Origin::signed(1), batch), Error::<Test>::BatchTooLarge);
+        assert_ok!(Erc1155::safe_batch_transfer(Origin::signed(1), 2, vec![(id, 1); 4]));
+
+        assert_eq!(
+            <Erc1155 as ERC1155<u64>>::balance_of_batch(vec![(1, id), (2, id), (3, id)].into_iter()),
+            Ok(vec![96, 4, 0])
+        );
+        assert_eq!(
+            <Erc1155 as ERC1155<u64>>::balance_of_batch(vec![(1, id); 5].into_iter()),
+            Err(Error::<Test>::BatchTooLarge.into())
+        );
+    });
+}
+
+#[test]
+fn batch_weight_is_refunded_for_duplicate_ids() {
+    new_test_ext().execute_with(|| {
+        let a = create(1, 100);
+        let b = create(1, 100);
+
+        // every entry is still processed and notified to the receiver, only the storage is refunded
+        let receiver_weight = RejectingReceiver::on_erc1155_batch_received_weight(3);
+        let info = Erc1155::safe_batch_transfer(Origin::signed(1), 2, vec![(a, 1), (b, 1), (a, 1)]).unwrap();
+        assert_eq!(info.actual_weight, Some(<() as WeightInfo>::safe_batch_transfer(3, 2) + receiver_weight));
+        let info = Erc1155::mint_batch(Origin::signed(1), 2, vec![(a, 1), (a, 1), (a, 1)]).unwrap();
+        assert_eq!(info.actual_weight, Some(<() as WeightInfo>::mint_batch(3, 1) + receiver_weight));
+        let info = Erc1155::burn_batch(Origin::signed(2), vec![(a, 1), (b, 1)]).unwrap();
+        assert_eq!(info.actual_weight, Some(<() as WeightInfo>::burn_batch(2, 2)));
+        assert!(<() as WeightInfo>::mint_batch(3, 1) > <() as WeightInfo>::mint_batch(1, 1));
+    });
+}
+
+fn genesis() -> crate::GenesisConfig<Test> {
+    crate::GenesisConfig {
+        initial_token: 0,
+        tokens: vec![
+            (1, 1, Some(1_000), 10, b"ipfs://one".to_vec()),
+            (5, 2, None, 0, Vec::new()),
+        ],
+        balances: vec![(1, 1, 100), (2, 1, 50), (2, 5, 7), (3, 0, 20)],
+        approvals: vec![(2, 3)],
+        base_uri: b"https://token/{id}.json".to_vec(),
+    }
+}
+
+#[test]
+fn genesis_creates_tokens_and_balances() {
+    new_test_ext_with(genesis()).execute_with(|| {
+        assert_eq!(Erc1155::issuance(0), Some(20));
+        assert_eq!(Erc1155::issuance(1), Some(150));
+        assert_eq!(Erc1155::issuance(5), Some(7));
+        assert_eq!(Erc1155::balance_of(2, 1), Some(50));
+        assert_eq!(Erc1155::token_details(1).unwrap().admin, 1);
+        assert_eq!(Erc1155::token_details(5).unwrap().issuer, 2);
+        assert_eq!(Erc1155::min_balance(&1), 10);
+        assert!(Erc1155::operator_approvals(2, 3));
+        assert_eq!(<Erc1155 as ERC1155MetadataURI<u64>>::uri(&1), b"ipfs://one".to_vec());
+        assert_eq!(<Erc1155 as ERC1155MetadataURI<u64>>::uri(&5).len(), "https://token/.json".len() + 64);
+        assert_ok!(Erc1155::ensure_issuance_invariant());
+
+        // ids after the declared tokens are free
+        assert_eq!(create(1, 10), 6);
+        assert_ok!(Erc1155::mint(Origin::signed(1), 4, 1, 850));
+        assert_noop!(Erc1155::mint(Origin::signed(1), 4, 1, 1), Error::<Test>::SupplyCapExceeded);
+    });
+}
+
+#[test]
+fn genesis_initial_token_is_never_created_again() {
+    let mut config = genesis();
+    config.initial_token = 8;
+    config.balances.clear();
+    new_test_ext_with(config).execute_with(|| {
+        assert_eq!(Erc1155::issuance(8), Some(0));
+        assert!(Erc1155::token_details(8).is_none());
+
+        assert_eq!(create(1, 10), 9);
+    });
+}
+
+#[test]
+#[should_panic(expected = "balance of undeclared token")]
+fn genesis_rejects_balances_of_unknown_tokens() {
+    let mut config = genesis();
+    config.balances.push((1, 2, 10));
+    new_test_ext_with(config);
+}
+
+#[test]
+#[should_panic(expected = "is below the minimum")]
+fn genesis_rejects_balances_below_the_minimum() {
+    let mut config = genesis();
+    config.balances.push((3, 1, 5));
+    new_test_ext_with(config);
+}
+
+#[test]
+#[should_panic(expected = "exceeds the max supply")]
+fn genesis_rejects_balances_above_the_max_supply() {
+    let mut config = genesis();
+    config.balances.push((3, 1, 900));
+    new_test_ext_with(config);
+}
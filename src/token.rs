@@ -1,6 +1,7 @@
-use crate::{Config, Balances, Event, Issuance, Error, imbalance::{PositiveImbalance, NegativeImbalance}, Pallet};
+use crate::{Config, Balances, ReservedBalances, NamedReserves, Locks, Event, Issuance, Error, imbalance::{PositiveImbalance, NegativeImbalance}, Pallet, erc1155::ERC1155Reservable, types::{ReserveData, BalanceLock}};
 use sp_runtime::{traits::{Zero, CheckedAdd, CheckedSub, Saturating, Bounded}};
-use frame_support::{traits::{Currency, Get, WithdrawReasons, ExistenceRequirement, SignedImbalance}, pallet_prelude::PhantomData, dispatch::{DispatchResult, DispatchError}};
+use sp_std::vec::Vec;
+use frame_support::{traits::{Currency, ReservableCurrency, NamedReservableCurrency, LockableCurrency, LockIdentifier, BalanceStatus, Get, WithdrawReasons, ExistenceRequirement, SignedImbalance}, pallet_prelude::PhantomData, dispatch::{DispatchResult, DispatchError}};
 
 pub struct Erc1155Token<T: Config, Inner: Get<T::TokenId>>(PhantomData<T>, PhantomData<Inner>);
 
@@ -9,6 +10,13 @@ impl<T: Config, Inner: Get<T::TokenId>> Erc1155Token<T, Inner> {
     pub fn new() -> Self {
         Self(PhantomData, PhantomData)
     }
+
+    /// Named reserves written back after `who` was reaped would outlive the reserved balance
+    fn clear_reaped_reserves(who: &T::AccountId) {
+        if <Self as ReservableCurrency<_>>::reserved_balance(who).is_zero() {
+            <NamedReserves<T>>::remove(who, Self::get());
+        }
+    }
 }
 
 impl<T: Config, Inner: Get<T::TokenId>> Get<T::TokenId> for Erc1155Token<T, Inner> {
@@ -23,15 +31,19 @@ where
     I: Get<T::TokenId>
 {
     type Balance = T::Balance;
-    type PositiveImbalance = PositiveImbalance<T>;
-    type NegativeImbalance = NegativeImbalance<T>;
+    type PositiveImbalance = PositiveImbalance<T, I>;
+    type NegativeImbalance = NegativeImbalance<T, I>;
 
     fn total_balance(who: &T::AccountId) -> Self::Balance {
-        <Balances<T>>::get(who, Self::get()).clone().unwrap_or(T::Balance::zero())
+        Self::free_balance(who).saturating_add(Self::reserved_balance(who))
     }
 
     fn can_slash(who: &T::AccountId, value: Self::Balance) -> bool {
-        value >= <Balances<T>>::get(who, Self::get()).unwrap_or(T::Balance::zero())
+        if value.is_zero() {
+            return true;
+        }
+
+        !<Pallet<T>>::is_fixed(&Self::get()) && Self::free_balance(who) >= value
     }
 
     fn total_issuance() -> Self::Balance {
@@ -39,12 +51,12 @@ where
     }
 
     fn minimum_balance() -> Self::Balance {
-        return 0u32.into();
+        <Pallet<T>>::min_balance(&Self::get())
     }
 
     fn burn(amount: Self::Balance) -> Self::PositiveImbalance {
-        if amount.is_zero() {
-            return Self::PositiveImbalance::new(0u32.into(), Self::get());
+        if amount.is_zero() || <Pallet<T>>::is_fixed(&Self::get()) {
+            return Self::PositiveImbalance::new(0u32.into());
         }
 
         let mut res = amount;
@@ -58,15 +70,16 @@ where
                 }));
         });
 
-        Self::PositiveImbalance::new(amount, Self::get())
+        Self::PositiveImbalance::new(res)
     }
 
     fn issue(amount: Self::Balance) -> Self::NegativeImbalance {
         if amount.is_zero() {
-            return Self::NegativeImbalance::new(0u32.into(), Self::get());
+            return Self::NegativeImbalance::new(0u32.into());
         }
 
-        let mut res = amount;
+        // never issue past the supply cap of the token
+        let mut res = amount.min(<Pallet<T>>::issuable(&Self::get()));
         <Issuance<T>>::mutate(Self::get(), |supply| {
             let sup = supply.unwrap_or(T::Balance::zero());
             *supply = Some(sup.checked_add(&res)
@@ -77,142 +90,443 @@ where
                 }));
         });
 
-        Self::NegativeImbalance::new(amount, Self::get())
+        Self::NegativeImbalance::new(res)
     }
 
     fn free_balance(who: &T::AccountId) -> Self::Balance {
-        Self::total_balance(who)
+        <Balances<T>>::get(who, Self::get()).unwrap_or(T::Balance::zero())
     }
 
     fn ensure_can_withdraw(
         who: &T::AccountId,
         _value: T::Balance,
-        _: WithdrawReasons,
+        reasons: WithdrawReasons,
         new_balance: T::Balance
     ) -> DispatchResult {
-        if Self::total_balance(who) < new_balance {
-            return Err(Error::<T>::OutOfFunds.into());
-        }
-
-        Ok(())
+        <Pallet<T>>::ensure_can_withdraw(who, &Self::get(), reasons, new_balance)
     }
 
     fn transfer(
         from: &T::AccountId,
         to: &T::AccountId,
         value: Self::Balance,
-        _existence_requirement: ExistenceRequirement
+        existence_requirement: ExistenceRequirement
     ) -> DispatchResult {
         if value.is_zero() || from == to {
             return Ok(())
         }
 
-        <Balances<T>>::try_mutate(from, Self::get(), |balance| -> Result<(), Error<T>> {
-            *balance = Some(balance.map(|b| b.checked_sub(&value))
-                .flatten()
-                .ok_or(Error::<T>::OutOfFunds)?);
-            <Balances<T>>::mutate(to, Self::get(), |balance_target| {
-                // Should we consider checked add?
-                *balance_target = Some(balance.unwrap().saturating_add(value));
-            });
-
-            Ok(())
-        })?;
+        <Pallet<T>>::do_transfer(from, to, &Self::get(), value, existence_requirement)?;
 
-        <Pallet<T>>::deposit_event(Event::TransferSingle(Some(from.clone()), Some(to.clone()), Self::get(), value));
+        <Pallet<T>>::deposit_event(Event::TransferSingle(from.clone(), Some(from.clone()), Some(to.clone()), Self::get(), value));
  
         Ok(())
     }
 
+    /// Slash the free balance first and the reserved balance for the rest, locks are ignored.
+    /// Nothing is slashed from a fixed supply token
     fn slash(
         who: &T::AccountId,
         value: Self::Balance
     ) -> (Self::NegativeImbalance, Self::Balance) {
-        let ret = |slashed, remaining| {
-            <Pallet<T>>::deposit_event(Event::TransferSingle(Some(who.clone()), None, Self::get(), slashed));
-            
-            (NegativeImbalance::new(slashed, Self::get()), remaining)
-        };
-
         if value.is_zero() {
-            return ret(T::Balance::zero(), Self::Balance::zero());
+            return (NegativeImbalance::new(Zero::zero()), Zero::zero());
+        }
+        if <Pallet<T>>::is_fixed(&Self::get()) {
+            return (NegativeImbalance::new(Zero::zero()), value);
         }
 
-        if Self::total_balance(who).is_zero() {
-            return ret(T::Balance::zero(), value);
+        let free = Self::free_balance(who);
+        let from_free = value.min(free);
+        let reserved = Self::reserved_balance(who);
+        let from_reserved = (value - from_free).min(reserved);
+        let slashed = from_free + from_reserved;
+        if slashed.is_zero() {
+            return (NegativeImbalance::new(Zero::zero()), value);
         }
 
-        <Balances<T>>::mutate(who, Self::get(), |balance| {
-            // Unwrap safety: balance is only None when Self::total_balance == 0
-            let balance: &mut Self::Balance = balance.as_mut().unwrap();
-            let slashed: Self::Balance;
-            let mut remaining = Self::Balance::zero();
-            if *balance < value {
-                slashed = *balance;
-                *balance = Self::Balance::zero();
-                remaining = value - *balance;
-            } else {
-                *balance = *balance - value;
-                slashed = value;
-            }
+        if !from_free.is_zero() {
+            <Pallet<T>>::write_balance(who, &Self::get(), free - from_free);
+        }
+        if !from_reserved.is_zero() {
+            <Pallet<T>>::write_reserved(who, &Self::get(), reserved - from_reserved);
+        }
+        <Pallet<T>>::reap(who, &Self::get());
+        <Pallet<T>>::deposit_event(Event::TransferSingle(who.clone(), Some(who.clone()), None, Self::get(), slashed));
 
-            ret(slashed, remaining)
-        })
+        (NegativeImbalance::new(slashed), value - slashed)
     }
 
     fn deposit_into_existing(
         who: &T::AccountId,
         value: Self::Balance
     ) -> Result<Self::PositiveImbalance, DispatchError> {
-        if value.is_zero() { return Ok(PositiveImbalance::new(0u32.into(), Self::get())) }
+        if value.is_zero() { return Ok(PositiveImbalance::new(0u32.into())) }
 
-        <Balances<T>>::try_mutate(who, Self::get(), |balance| {
-            // checked add?
-            *balance = Some(balance.ok_or(Error::<T>::AccountNotFound)?.saturating_add(value));
-            Ok(PositiveImbalance::new(value, Self::get()))
-        })
+        if <Pallet<T>>::total_of(who, &Self::get()).is_zero() {
+            return Err(Error::<T>::AccountNotFound.into());
+        }
+        <Pallet<T>>::ensure_can_issue(&Self::get(), value)?;
+        let credited = Self::free_balance(who).checked_add(&value).ok_or(Error::<T>::Overflow)?;
+        <Pallet<T>>::write_balance(who, &Self::get(), credited);
+        Ok(PositiveImbalance::new(value))
     }
 
     fn deposit_creating(
         who: &T::AccountId,
         value: Self::Balance
     ) -> Self::PositiveImbalance {
-        if value.is_zero() { return PositiveImbalance::new(0u32.into(), Self::get()) }
+        if value.is_zero() { return PositiveImbalance::new(0u32.into()) }
+        if <Pallet<T>>::ensure_can_issue(&Self::get(), value).is_err()
+            || <Pallet<T>>::ensure_can_receive(who, &Self::get(), value).is_err()
+        {
+            return PositiveImbalance::new(0u32.into())
+        }
 
-        <Balances<T>>::mutate(who, Self::get(), |balance| {
-            // checked add?
-            *balance = Some(balance.unwrap_or(Self::Balance::zero()).saturating_add(value));
-            PositiveImbalance::new(value, Self::get())
-        })
+        match Self::free_balance(who).checked_add(&value) {
+            Some(credited) => {
+                <Pallet<T>>::write_balance(who, &Self::get(), credited);
+                PositiveImbalance::new(value)
+            },
+            None => PositiveImbalance::new(0u32.into())
+        }
     }
 
     fn withdraw(
         who: &T::AccountId,
         value: Self::Balance,
-        _: WithdrawReasons,
-        _: ExistenceRequirement
+        reasons: WithdrawReasons,
+        liveness: ExistenceRequirement
     ) -> Result<Self::NegativeImbalance, DispatchError> {
-        <Balances<T>>::try_mutate(who, Self::get(), |balance| {
-            *balance = Some(balance
-                .map(|b| b.checked_sub(&value))
-                .flatten()
-                .ok_or(Error::<T>::OutOfFunds)?);
+        if value.is_zero() { return Ok(NegativeImbalance::new(0u32.into())) }
+        if <Pallet<T>>::is_fixed(&Self::get()) {
+            return Err(Error::<T>::FixedSupply.into());
+        }
 
-            Ok(Self::NegativeImbalance::new(value, Self::get()))
-        })
+        <Pallet<T>>::ensure_keep_alive(who, &Self::get(), value, liveness)?;
+        <Pallet<T>>::debit(who, &Self::get(), value, reasons)?;
+        <Pallet<T>>::reap(who, &Self::get());
+        Ok(Self::NegativeImbalance::new(value))
     }
 
     fn make_free_balance_be(who: &T::AccountId, value: Self::Balance) -> SignedImbalance<Self::Balance, Self::PositiveImbalance> {
-        <Balances<T>>::mutate(who, Self::get(), |balance| {
-            let bal = balance.unwrap_or(T::Balance::zero());
-            let im = if value > bal {
-                SignedImbalance::Negative(NegativeImbalance::new(value - bal, Self::get()))
-            } else {
-                SignedImbalance::Positive(PositiveImbalance::new(bal - value, Self::get()))
+        let bal = Self::free_balance(who);
+        if value > bal {
+            let increase = value - bal;
+            // like `deposit_creating`, the balance is left unchanged when the increase can't be issued
+            if <Pallet<T>>::ensure_can_issue(&Self::get(), increase).is_err()
+                || <Pallet<T>>::ensure_can_receive(who, &Self::get(), increase).is_err()
+            {
+                return SignedImbalance::Positive(PositiveImbalance::new(0u32.into()))
+            }
+
+            <Pallet<T>>::write_balance(who, &Self::get(), value);
+            return SignedImbalance::Positive(PositiveImbalance::new(increase))
+        }
+        // the balance of a fixed supply token can't shrink either
+        if <Pallet<T>>::is_fixed(&Self::get()) {
+            return SignedImbalance::Positive(PositiveImbalance::new(0u32.into()))
+        }
+
+        <Pallet<T>>::write_balance(who, &Self::get(), value);
+        <Pallet<T>>::reap(who, &Self::get());
+
+        SignedImbalance::Negative(NegativeImbalance::new(bal - value))
+    }
+}
+
+impl<T, I> ReservableCurrency<T::AccountId> for Erc1155Token<T, I>
+where
+    T: Config,
+    I: Get<T::TokenId>
+{
+    fn can_reserve(who: &T::AccountId, value: Self::Balance) -> bool {
+        Self::free_balance(who)
+            .checked_sub(&value)
+            .map_or(false, |new_balance| {
+                <Pallet<T>>::ensure_can_withdraw(who, &Self::get(), WithdrawReasons::RESERVE, new_balance).is_ok()
+            })
+    }
+
+    fn reserved_balance(who: &T::AccountId) -> Self::Balance {
+        <ReservedBalances<T>>::get(who, Self::get()).unwrap_or(T::Balance::zero())
+    }
+
+    fn reserve(who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+        <Pallet<T>>::lock(who, &Self::get(), value)
+    }
+
+    fn unreserve(who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+        let actual = value.min(Self::reserved_balance(who));
+        // can't fail, actual never exceeds the reserved balance
+        let _ = <Pallet<T>>::unlock(who, &Self::get(), actual);
+
+        value - actual
+    }
+
+    fn slash_reserved(
+        who: &T::AccountId,
+        value: Self::Balance
+    ) -> (Self::NegativeImbalance, Self::Balance) {
+        let actual = value.min(Self::reserved_balance(who));
+        if actual.is_zero() || <Pallet<T>>::is_fixed(&Self::get()) {
+            return (NegativeImbalance::new(T::Balance::zero()), value);
+        }
+
+        <Pallet<T>>::write_reserved(who, &Self::get(), Self::reserved_balance(who) - actual);
+        <Pallet<T>>::reap(who, &Self::get());
+        <Pallet<T>>::deposit_event(Event::TransferSingle(who.clone(), Some(who.clone()), None, Self::get(), actual));
+
+        (NegativeImbalance::new(actual), value - actual)
+    }
+
+    fn repatriate_reserved(
+        slashed: &T::AccountId,
+        beneficiary: &T::AccountId,
+        value: Self::Balance,
+        status: BalanceStatus
+    ) -> Result<Self::Balance, DispatchError> {
+        if slashed == beneficiary {
+            return match status {
+                BalanceStatus::Free => Ok(Self::unreserve(slashed, value)),
+                BalanceStatus::Reserved => Ok(value.saturating_sub(Self::reserved_balance(slashed))),
             };
-            *balance = Some(value);
+        }
+
+        let actual = <Pallet<T>>::move_reserved(slashed, beneficiary, &Self::get(), value, status)?;
+        if !actual.is_zero() {
+            <Pallet<T>>::deposit_event(Event::TransferSingle(slashed.clone(), Some(slashed.clone()), Some(beneficiary.clone()), Self::get(), actual));
+        }
+
+        Ok(value - actual)
+    }
+}
+
+impl<T, I> NamedReservableCurrency<T::AccountId> for Erc1155Token<T, I>
+where
+    T: Config,
+    I: Get<T::TokenId>
+{
+    type ReserveIdentifier = T::ReserveIdentifier;
+
+    fn reserved_balance_named(id: &Self::ReserveIdentifier, who: &T::AccountId) -> Self::Balance {
+        let reserves = <NamedReserves<T>>::get(who, Self::get());
+        reserves
+            .binary_search_by_key(id, |data| data.id)
+            .map(|index| reserves[index].amount)
+            .unwrap_or(T::Balance::zero())
+    }
 
-            im
+    fn reserve_named(
+        id: &Self::ReserveIdentifier,
+        who: &T::AccountId,
+        value: Self::Balance
+    ) -> DispatchResult {
+        if value.is_zero() {
+            return Ok(());
+        }
+
+        <NamedReserves<T>>::try_mutate(who, Self::get(), |reserves| -> DispatchResult {
+            match reserves.binary_search_by_key(id, |data| data.id) {
+                Ok(index) => {
+                    reserves[index].amount = reserves[index].amount.checked_add(&value).ok_or(Error::<T>::Overflow)?;
+                },
+                Err(index) => {
+                    reserves.try_insert(index, ReserveData { id: *id, amount: value })
+                        .map_err(|_| Error::<T>::TooManyReserves)?;
+                }
+            };
+
+            <Self as ReservableCurrency<_>>::reserve(who, value)
+        })
+    }
+
+    fn unreserve_named(
+        id: &Self::ReserveIdentifier,
+        who: &T::AccountId,
+        value: Self::Balance
+    ) -> Self::Balance {
+        if value.is_zero() {
+            return Zero::zero();
+        }
+
+        <NamedReserves<T>>::mutate(who, Self::get(), |reserves| {
+            match reserves.binary_search_by_key(id, |data| data.id) {
+                Ok(index) => {
+                    let to_change = value.min(reserves[index].amount);
+                    let remain = <Self as ReservableCurrency<_>>::unreserve(who, to_change);
+                    let actual = to_change - remain;
+
+                    reserves[index].amount -= actual;
+                    if reserves[index].amount.is_zero() {
+                        reserves.remove(index);
+                    }
+
+                    value - actual
+                },
+                Err(_) => value
+            }
         })
     }
+
+    fn slash_reserved_named(
+        id: &Self::ReserveIdentifier,
+        who: &T::AccountId,
+        value: Self::Balance
+    ) -> (Self::NegativeImbalance, Self::Balance) {
+        if value.is_zero() {
+            return (NegativeImbalance::new(T::Balance::zero()), Zero::zero());
+        }
+
+        let res = <NamedReserves<T>>::mutate(who, Self::get(), |reserves| {
+            match reserves.binary_search_by_key(id, |data| data.id) {
+                Ok(index) => {
+                    let to_change = value.min(reserves[index].amount);
+                    let (imbalance, remain) = <Self as ReservableCurrency<_>>::slash_reserved(who, to_change);
+                    let actual = to_change - remain;
+
+                    reserves[index].amount -= actual;
+                    if reserves[index].amount.is_zero() {
+                        reserves.remove(index);
+                    }
+
+                    (imbalance, value - actual)
+                },
+                Err(_) => (NegativeImbalance::new(T::Balance::zero()), value)
+            }
+        });
+        Self::clear_reaped_reserves(who);
+
+        res
+    }
+
+    fn repatriate_reserved_named(
+        id: &Self::ReserveIdentifier,
+        slashed: &T::AccountId,
+        beneficiary: &T::AccountId,
+        value: Self::Balance,
+        status: BalanceStatus
+    ) -> Result<Self::Balance, DispatchError> {
+        if value.is_zero() {
+            return Ok(Zero::zero());
+        }
+
+        if slashed == beneficiary {
+            return match status {
+                BalanceStatus::Free => Ok(Self::unreserve_named(id, slashed, value)),
+                BalanceStatus::Reserved => Ok(value.saturating_sub(Self::reserved_balance_named(id, slashed))),
+            };
+        }
+
+        let res = <NamedReserves<T>>::try_mutate(slashed, Self::get(), |reserves| -> Result<Self::Balance, DispatchError> {
+            let index = match reserves.binary_search_by_key(id, |data| data.id) {
+                Ok(index) => index,
+                Err(_) => return Ok(value)
+            };
+            let to_change = value.min(reserves[index].amount);
+
+            let actual = if status == BalanceStatus::Reserved {
+                // keep the funds under the same identifier for the beneficiary
+                <NamedReserves<T>>::try_mutate(beneficiary, Self::get(), |b_reserves| -> Result<Self::Balance, DispatchError> {
+                    let b_index = match b_reserves.binary_search_by_key(id, |data| data.id) {
+                        Ok(b_index) => b_index,
+                        Err(b_index) => {
+                            b_reserves.try_insert(b_index, ReserveData { id: *id, amount: Zero::zero() })
+                                .map_err(|_| Error::<T>::TooManyReserves)?;
+                            b_index
+                        }
+                    };
+
+                    let remain = <Self as ReservableCurrency<_>>::repatriate_reserved(slashed, beneficiary, to_change, status)?;
+                    let actual = to_change - remain;
+                    b_reserves[b_index].amount = b_reserves[b_index].amount.checked_add(&actual).ok_or(Error::<T>::Overflow)?;
+
+                    Ok(actual)
+                })?
+            } else {
+                let remain = <Self as ReservableCurrency<_>>::repatriate_reserved(slashed, beneficiary, to_change, status)?;
+                to_change - remain
+            };
+
+            reserves[index].amount -= actual;
+            if reserves[index].amount.is_zero() {
+                reserves.remove(index);
+            }
+
+            Ok(value - actual)
+        });
+        Self::clear_reaped_reserves(slashed);
+
+        res
+    }
+}
+
+impl<T, I> LockableCurrency<T::AccountId> for Erc1155Token<T, I>
+where
+    T: Config,
+    I: Get<T::TokenId>
+{
+    type Moment = T::BlockNumber;
+    type MaxLocks = T::MaxLocks;
+
+    fn set_lock(
+        id: LockIdentifier,
+        who: &T::AccountId,
+        amount: Self::Balance,
+        reasons: WithdrawReasons
+    ) {
+        if amount.is_zero() || reasons.is_empty() {
+            return;
+        }
+
+        let mut new_lock = Some(BalanceLock { id, amount, reasons });
+        let mut locks = <Locks<T>>::get(who, Self::get())
+            .into_iter()
+            .filter_map(|lock| if lock.id == id { new_lock.take() } else { Some(lock) })
+            .collect::<Vec<_>>();
+        if let Some(lock) = new_lock {
+            locks.push(lock);
+        }
+
+        <Pallet<T>>::update_locks(who, &Self::get(), locks);
+    }
+
+    fn extend_lock(
+        id: LockIdentifier,
+        who: &T::AccountId,
+        amount: Self::Balance,
+        reasons: WithdrawReasons
+    ) {
+        if amount.is_zero() || reasons.is_empty() {
+            return;
+        }
+
+        let mut new_lock = Some(BalanceLock { id, amount, reasons });
+        let mut locks = <Locks<T>>::get(who, Self::get())
+            .into_iter()
+            .filter_map(|lock| if lock.id == id {
+                new_lock.take().map(|nl| BalanceLock {
+                    id: lock.id,
+                    amount: lock.amount.max(nl.amount),
+                    reasons: lock.reasons | nl.reasons,
+                })
+            } else {
+                Some(lock)
+            })
+            .collect::<Vec<_>>();
+        if let Some(lock) = new_lock {
+            locks.push(lock);
+        }
+
+        <Pallet<T>>::update_locks(who, &Self::get(), locks);
+    }
+
+    fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+        let locks = <Locks<T>>::get(who, Self::get())
+            .into_iter()
+            .filter(|lock| lock.id != id)
+            .collect::<Vec<_>>();
+
+        <Pallet<T>>::update_locks(who, &Self::get(), locks);
+    }
 }
@@ -0,0 +1,56 @@
+use codec::{Encode, Decode};
+use frame_support::traits::{LockIdentifier, WithdrawReasons};
+use sp_runtime::RuntimeDebug;
+
+/// Balance reserved under a named identifier
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ReserveData<ReserveIdentifier, Balance> {
+    pub id: ReserveIdentifier,
+    pub amount: Balance,
+}
+
+/// Lock on the free balance of an account for a single token
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct BalanceLock<Balance> {
+    pub id: LockIdentifier,
+    pub amount: Balance,
+    /// Withdrawals this lock applies to
+    pub reasons: WithdrawReasons,
+}
+
+/// Roles and state of a token
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct TokenDetails<AccountId, Balance> {
+    /// Account that created the token
+    pub creator: AccountId,
+    /// Can change the roles of the token and burn from any account
+    pub admin: AccountId,
+    /// Can mint the token
+    pub issuer: AccountId,
+    /// Can freeze and thaw the token
+    pub freezer: AccountId,
+    /// Transfers of a frozen token are rejected
+    pub is_frozen: bool,
+    /// Maximum issuance of the token, None when uncapped
+    pub max_supply: Option<Balance>,
+    /// Issuance can never change after creation, neither minting nor burning is possible
+    pub fixed_supply: bool,
+    /// Balances below this amount are removed as dust
+    pub min_balance: Balance,
+}
+
+impl<AccountId: Clone, Balance> TokenDetails<AccountId, Balance> {
+    /// Details of a new token where `creator` holds every role
+    pub fn new(creator: AccountId, max_supply: Option<Balance>, fixed_supply: bool, min_balance: Balance) -> Self {
+        Self {
+            admin: creator.clone(),
+            issuer: creator.clone(),
+            freezer: creator.clone(),
+            creator,
+            is_frozen: false,
+            max_supply,
+            fixed_supply,
+            min_balance,
+        }
+    }
+}
@@ -1,26 +1,227 @@
-/// Weight info related to freezer
+//! Weights for pallet_erc1155
+//!
+//! `SubstrateWeight` is the implementation runtimes use, `()` the fallback for tests and mocks.
+//! Regenerate this file from the benchmarks in `benchmarking.rs` on the reference hardware whenever
+//! the calls change:
+//! `./target/release/node benchmark --chain=dev --steps=50 --repeat=20 --pallet=pallet_erc1155
+//! --extrinsic=* --execution=wasm --wasm-execution=compiled --heap-pages=4096 --output=./src/weights.rs`
+//!
+//! The benchmarks measure the worst case of the calls moving balances: the sender holds `MaxLocks`
+//! locks and is reaped, handing its dust to `DustRemoval`. The weight `Receiver` declares is charged
+//! on top, so benchmark with a runtime whose `Receiver` does no work.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
 use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
 use sp_std::marker::PhantomData;
 
+/// Weight functions needed for pallet_erc1155, one per dispatchable.
+/// Batch weights take the number of entries `n` and the number of distinct ids `d` among them.
+/// Their benchmarks vary `d` and the `r` entries repeating an id independently, with `n = d + r`
 pub trait WeightInfo {
-    fn transfer() -> Weight;
+    fn safe_transfer() -> Weight;
+    fn safe_batch_transfer(n: u32, d: u32) -> Weight;
+    fn set_approval_for_all() -> Weight;
+    fn transfer_from() -> Weight;
+    fn burn() -> Weight;
+    fn admin_burn() -> Weight;
+    fn burn_batch(n: u32, d: u32) -> Weight;
+    fn create_token() -> Weight;
+    fn create_unique_token() -> Weight;
+    fn mint() -> Weight;
+    fn mint_batch(n: u32, d: u32) -> Weight;
+    fn set_uri() -> Weight;
+    fn transfer_ownership() -> Weight;
+    fn set_team() -> Weight;
+    fn freeze() -> Weight;
+    fn thaw() -> Weight;
+    fn balance_of_batch(n: u32) -> Weight;
 }
 
+/// Weights for pallet_erc1155 using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-    // TODO: proper weights
-	fn transfer() -> Weight {
-		(10_000_000 as Weight)
+	fn safe_transfer() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn safe_batch_transfer(n: u32, d: u32) -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add((6_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add((20_500_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads((7 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+	}
+	fn set_approval_for_all() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn transfer_from() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn burn() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn admin_burn() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn burn_batch(n: u32, d: u32) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((5_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add((19_000_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+	}
+	fn create_token() -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn create_unique_token() -> Weight {
+		(34_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn mint() -> Weight {
+		(37_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn mint_batch(n: u32, d: u32) -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add((5_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add((18_000_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+	}
+	fn set_uri() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn transfer_ownership() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_team() -> Weight {
+		(23_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn freeze() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn thaw() -> Weight {
+		(21_000_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
+	fn balance_of_batch(n: u32) -> Weight {
+		(2_000_000 as Weight)
+			.saturating_add((4_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads((n as Weight)))
+	}
 }
 
+// For backwards compatibility and tests
 impl WeightInfo for () {
-    // TODO: Proper weights
-	fn transfer() -> Weight {
-		(10_000_000 as Weight)
+	fn safe_transfer() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn safe_batch_transfer(n: u32, d: u32) -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add((6_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add((20_500_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads((7 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+	}
+	fn set_approval_for_all() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn transfer_from() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn burn() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn admin_burn() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn burn_batch(n: u32, d: u32) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((5_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add((19_000_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads((6 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+	}
+	fn create_token() -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn create_unique_token() -> Weight {
+		(34_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn mint() -> Weight {
+		(37_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn mint_batch(n: u32, d: u32) -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add((5_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add((18_000_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+	}
+	fn set_uri() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn transfer_ownership() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_team() -> Weight {
+		(23_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn freeze() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn thaw() -> Weight {
+		(21_000_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
+	fn balance_of_batch(n: u32) -> Weight {
+		(2_000_000 as Weight)
+			.saturating_add((4_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads((n as Weight)))
+	}
 }